@@ -12,7 +12,7 @@ use std::f32::consts::PI;
 use glutin::GlContext;
 use gl::types::*;
 use gl_context::{
-    Context, TextureKind, TextureFormat, TextureWrap, FilterMode,
+    Context, TextureKind, TextureFormat, TextureWrap, FilterMode, ImageOptions,
     BufferTarget, DrawMode, Usage
 };
 use prng::Prng;
@@ -110,11 +110,10 @@ fn main() {
         &context,
         TEX_WIDTH,
         TEX_HEIGHT,
-        TextureFormat::RGBA,
-        TextureKind::UnsignedByte,
-        TextureWrap::Repeat,
-        FilterMode::None,
-        true,
+        ImageOptions::new(TextureFormat::RGBA, TextureKind::UnsignedByte)
+            .set_wrap(TextureWrap::Repeat)
+            .set_filter(FilterMode::None)
+            .set_generate_mipmap(true),
         &data
     );
 
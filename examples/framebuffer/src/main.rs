@@ -6,8 +6,9 @@ extern crate gl_context;
 use glutin::GlContext;
 use gl::types::*;
 use gl_context::{
-    Context, TextureKind, TextureFormat, TextureWrap, FilterMode,
-    Attachment, Usage, DrawMode, BufferTarget
+    Context, TextureKind, TextureFormat, FilterMode, ImageOptions,
+    Attachment, InternalFormat, Usage, DrawMode, BufferTarget,
+    RenderPass, LoadAction, StoreAction
 };
 
 
@@ -98,24 +99,19 @@ fn main() {
     );
 
 
+    let mut fb_options = ImageOptions::new(TextureFormat::RGBA, TextureKind::UnsignedByte);
+    fb_options.set_filter(FilterMode::None);
+
     let mut fb_texture = context.new_texture();
-    fb_texture.set_null2d(
-        &context,
-        256,
-        256,
-        TextureFormat::RGBA,
-        TextureKind::UnsignedByte,
-        TextureWrap::Clamp,
-        FilterMode::None,
-        false
-    );
+    fb_texture.set_null2d(&context, 256, 256, &fb_options);
 
 
     let mut framebuffer = context.new_framebuffer();
-    framebuffer.set(&context, &fb_texture, &[Attachment::Color], 0);
+    framebuffer.attach_texture(&mut context, Attachment::Color(0), &fb_texture, 0);
 
-    let renderbuffer = context.new_renderbuffer();
-    renderbuffer.set(&context, TextureFormat::DepthComponent, Attachment::Depth, 256, 256);
+    let mut renderbuffer = context.new_renderbuffer();
+    renderbuffer.set_storage(&mut context, InternalFormat::DepthComponent16, 256, 256, 0);
+    framebuffer.attach_renderbuffer(&mut context, Attachment::Depth, &renderbuffer);
 
 
     let mut fb_program = context.new_program();
@@ -154,29 +150,26 @@ fn main() {
                     width = w as usize;
                     height = h as usize;
 
-                    fb_texture.set_null2d(
-                        &context,
-                        width / SIZE,
-                        height / SIZE,
-                        TextureFormat::RGBA,
-                        TextureKind::UnsignedByte,
-                        TextureWrap::Clamp,
-                        FilterMode::None,
-                        false
-                    );
-                    framebuffer.set(&context, &fb_texture, &[Attachment::Color], 0);
-                    renderbuffer.set(&context, TextureFormat::DepthComponent, Attachment::Depth, width / SIZE, height / SIZE);
+                    fb_texture.set_null2d(&context, width / SIZE, height / SIZE, &fb_options);
+                    framebuffer.attach_texture(&mut context, Attachment::Color(0), &fb_texture, 0);
+                    renderbuffer.set_storage(&mut context, InternalFormat::DepthComponent16, width / SIZE, height / SIZE, 0);
+                    framebuffer.attach_renderbuffer(&mut context, Attachment::Depth, &renderbuffer);
                 },
                 _ => (),
             }
         });
 
-        context.set_framebuffer(&framebuffer, false);
-        context.set_renderbuffer(&renderbuffer, false);
+        let mut offscreen_pass = RenderPass::new(Some(&framebuffer), width / SIZE, height / SIZE);
+        offscreen_pass
+            .add_color(Attachment::Color(0), LoadAction::Clear, StoreAction::Store, &[0.3, 0.3, 0.3, 1.0])
+            .set_depth(Attachment::Depth, LoadAction::Clear, StoreAction::Discard, 1.0, 0);
 
-        context.set_viewport(0, 0, width / SIZE, height / SIZE);
-        context.clear(true, true, true);
-        context.set_clear_color(&[0.3, 0.3, 0.3, 1.0]);
+        let mut screen_pass = RenderPass::new(None, width, height);
+        screen_pass
+            .add_color(Attachment::Color(0), LoadAction::Clear, StoreAction::Store, &[0.0, 0.0, 0.0, 1.0])
+            .set_depth(Attachment::Depth, LoadAction::Clear, StoreAction::Store, 1.0, 0);
+
+        offscreen_pass.begin(&mut context);
 
         context.set_program(&tr_program, false);
         context.set_vertex_array(&tr_vertex_array, false);
@@ -185,12 +178,10 @@ fn main() {
 
         context.draw_arrays(DrawMode::Triangles, 0, 3);
 
+        offscreen_pass.end(&mut context);
 
-        context.remove_framebuffer(false);
-        context.remove_renderbuffer(false);
 
-        context.set_viewport(0, 0, width, height);
-        context.clear(true, true, true);
+        screen_pass.begin(&mut context);
 
         context.set_program(&fb_program, false);
         context.set_vertex_array(&fb_vertex_array, false);
@@ -201,6 +192,8 @@ fn main() {
 
         context.draw_arrays(DrawMode::TriangleStrip, 0, 4);
 
+        screen_pass.end(&mut context);
+
         gl_window.swap_buffers().unwrap();
     }
 }
@@ -5,6 +5,8 @@ use gl;
 use gl::types::*;
 
 use super::enums::{BufferTarget, Usage};
+use context::Context;
+use debug;
 
 
 pub struct Buffer {
@@ -24,6 +26,7 @@ impl Drop for Buffer {
     fn drop(&mut self) {
         if self.id != 0 {
             unsafe { gl::DeleteBuffers(1, &self.id); }
+            gl_check!("Buffer::drop");
         }
     }
 }
@@ -36,6 +39,7 @@ impl Buffer {
             id: {
                 let mut id = 0;
                 unsafe { gl::GenBuffers(1, &mut id); }
+                gl_check!("Buffer::new");
                 id
             },
 
@@ -52,6 +56,12 @@ impl Buffer {
     #[inline(always)]
     pub fn id(&self) -> GLuint { self.id }
 
+    #[inline]
+    pub fn set_label(&self, context: &Context, label: &str) -> &Self {
+        debug::object_label(context, gl::BUFFER, self.id, label);
+        self
+    }
+
     #[inline(always)]
     pub fn stride(&self) -> usize { self.stride }
     #[inline(always)]
@@ -79,6 +89,7 @@ impl Buffer {
     		gl::BufferData(kind, size as GLsizeiptr, array.as_ptr() as *const _, usage);
     		gl::BindBuffer(kind, 0);
         };
+        gl_check!("Buffer::set");
 
         self.stride = stride;
         self.kind = kind;
@@ -1,7 +1,8 @@
+use alloc::boxed::Box;
 use alloc::string::String;
 use alloc::vec::Vec;
 
-use core::mem;
+use core::ops::{Deref, DerefMut, Drop};
 
 use gl;
 use gl::types::*;
@@ -14,14 +15,160 @@ use texture::Texture;
 use vertex_array::VertexArray;
 use framebuffer::Framebuffer;
 use renderbuffer::Renderbuffer;
+use sampler::{self, Sampler};
+use render_state::{RenderState, Stencil};
+use error::{self, GlError};
+use debug::{self, DebugCallback, DebugMessage};
+#[cfg(debug_assertions)]
+use verify::{self, StateMismatch};
 
 static HIGHP: &'static str = "highp";
 static MEDIUMP: &'static str = "mediump";
 static LOWP: &'static str = "lowp";
 
 
+#[derive(Debug, Clone, PartialEq)]
+pub struct ContextState {
+    render_state: RenderState,
+
+    clear_color: [f32; 4],
+    clear_color_valid: bool,
+    clear_depth: f64,
+    clear_stencil: isize,
+
+    enabled_attributes: Vec<bool>,
+
+    viewport_x: usize,
+    viewport_y: usize,
+    viewport_width: usize,
+    viewport_height: usize,
+    viewport_valid: bool,
+
+    current_buffer: GLuint,
+    current_buffer_kind: GLenum,
+    current_vertex_array: GLuint,
+    current_framebuffer: GLuint,
+    current_read_framebuffer: GLuint,
+    current_renderbuffer: GLuint,
+
+    current_program: GLuint,
+
+    texture_index: GLuint,
+    current_texture_index: GLint,
+    current_texture: GLuint,
+    current_texture_kind: GLenum,
+
+    current_samplers: Vec<GLuint>,
+}
+
+impl ContextState {
+    #[inline(always)]
+    pub fn render_state(&self) -> &RenderState { &self.render_state }
+
+    #[inline(always)]
+    pub fn clear_color(&self) -> &[f32; 4] { &self.clear_color }
+    #[inline(always)]
+    pub fn clear_color_valid(&self) -> bool { self.clear_color_valid }
+    #[inline(always)]
+    pub fn clear_depth(&self) -> f64 { self.clear_depth }
+    #[inline(always)]
+    pub fn clear_stencil(&self) -> isize { self.clear_stencil }
+
+    #[inline(always)]
+    pub fn enabled_attributes(&self) -> &[bool] { &self.enabled_attributes }
+
+    #[inline(always)]
+    pub fn viewport_x(&self) -> usize { self.viewport_x }
+    #[inline(always)]
+    pub fn viewport_y(&self) -> usize { self.viewport_y }
+    #[inline(always)]
+    pub fn viewport_width(&self) -> usize { self.viewport_width }
+    #[inline(always)]
+    pub fn viewport_height(&self) -> usize { self.viewport_height }
+    #[inline(always)]
+    pub fn viewport_valid(&self) -> bool { self.viewport_valid }
+
+    #[inline(always)]
+    pub fn current_buffer(&self) -> GLuint { self.current_buffer }
+    #[inline(always)]
+    pub fn current_buffer_kind(&self) -> GLenum { self.current_buffer_kind }
+    #[inline(always)]
+    pub fn current_vertex_array(&self) -> GLuint { self.current_vertex_array }
+    #[inline(always)]
+    pub fn current_framebuffer(&self) -> GLuint { self.current_framebuffer }
+    #[inline(always)]
+    pub fn current_read_framebuffer(&self) -> GLuint { self.current_read_framebuffer }
+    #[inline(always)]
+    pub fn current_renderbuffer(&self) -> GLuint { self.current_renderbuffer }
+
+    #[inline(always)]
+    pub fn current_program(&self) -> GLuint { self.current_program }
+
+    #[inline(always)]
+    pub fn texture_index(&self) -> GLuint { self.texture_index }
+    #[inline(always)]
+    pub fn current_texture_index(&self) -> GLint { self.current_texture_index }
+    #[inline(always)]
+    pub fn current_texture(&self) -> GLuint { self.current_texture }
+    #[inline(always)]
+    pub fn current_texture_kind(&self) -> GLenum { self.current_texture_kind }
+
+    #[inline(always)]
+    pub fn current_samplers(&self) -> &[GLuint] { &self.current_samplers }
+}
+
+
+pub struct StateGuard<'a> {
+    context: &'a mut Context,
+}
+
+impl<'a> Deref for StateGuard<'a> {
+    type Target = Context;
+
+    #[inline(always)]
+    fn deref(&self) -> &Context { self.context }
+}
+
+impl<'a> DerefMut for StateGuard<'a> {
+    #[inline(always)]
+    fn deref_mut(&mut self) -> &mut Context { self.context }
+}
+
+impl<'a> Drop for StateGuard<'a> {
+    #[inline]
+    fn drop(&mut self) {
+        self.context.pop_state();
+    }
+}
+
+
+pub struct DebugGroup<'a> {
+    context: &'a mut Context,
+}
+
+impl<'a> Deref for DebugGroup<'a> {
+    type Target = Context;
+
+    #[inline(always)]
+    fn deref(&self) -> &Context { self.context }
+}
+
+impl<'a> DerefMut for DebugGroup<'a> {
+    #[inline(always)]
+    fn deref_mut(&mut self) -> &mut Context { self.context }
+}
+
+impl<'a> Drop for DebugGroup<'a> {
+    #[inline]
+    fn drop(&mut self) {
+        self.context.pop_debug_group();
+    }
+}
+
+
 pub struct Context {
     version: String,
+    is_es: bool,
 
     major: usize,
     minor: usize,
@@ -31,13 +178,21 @@ pub struct Context {
     extenstions: Vec<String>,
 
     clear_color: [f32; 4],
+    clear_color_valid: bool,
 
     max_anisotropy: usize,
     max_textures: usize,
     max_vertex_textures: usize,
     max_texture_size: usize,
     max_cube_texture_size: usize,
+    max_3d_texture_size: usize,
+    max_array_texture_layers: usize,
     max_render_buffer_size: usize,
+    max_samples: usize,
+    max_integer_samples: usize,
+    max_depth_texture_samples: usize,
+    max_color_attachments: usize,
+    max_draw_buffers: usize,
 
     max_uniforms: usize,
     max_varyings: usize,
@@ -51,6 +206,7 @@ pub struct Context {
     viewport_y: usize,
     viewport_width: usize,
     viewport_height: usize,
+    viewport_valid: bool,
 
     blending: Blending,
     cull_face: CullFace,
@@ -59,6 +215,8 @@ pub struct Context {
     blending_disabled: bool,
     cull_face_disabled: bool,
     depth_test_disabled: bool,
+    stencil_test_disabled: bool,
+    scissor_test_disabled: bool,
 
     clear_depth: f64,
     clear_stencil: isize,
@@ -68,10 +226,15 @@ pub struct Context {
     depth_range_far: f64,
     line_width: f32,
 
+    stencil: Stencil,
+    scissor: Option<[usize; 4]>,
+    color_mask: [bool; 4],
+
     current_buffer: GLuint,
     current_buffer_kind: GLenum,
     current_vertex_array: GLuint,
     current_framebuffer: GLuint,
+    current_read_framebuffer: GLuint,
     current_renderbuffer: GLuint,
 
     current_program: GLuint,
@@ -80,6 +243,22 @@ pub struct Context {
     texture_index: GLuint,
     current_texture_index: GLint,
     current_texture: GLuint,
+    current_texture_kind: GLenum,
+
+    current_samplers: Vec<GLuint>,
+
+    state_stack: Vec<ContextState>,
+    verify_after_draw: bool,
+
+    debug_callback: Option<Box<DebugCallback>>,
+    debug_group_depth: usize,
+}
+
+impl Drop for Context {
+    #[inline]
+    fn drop(&mut self) {
+        self.remove_debug_callback();
+    }
 }
 
 impl Context {
@@ -88,6 +267,7 @@ impl Context {
     pub fn new() -> Self {
         Context {
             version: String::new(),
+            is_es: false,
 
             major: 0,
             minor: 0,
@@ -97,13 +277,21 @@ impl Context {
             extenstions: Vec::new(),
 
             clear_color: [0f32, 0f32, 0f32, 1f32],
+            clear_color_valid: true,
 
             max_anisotropy: 0,
             max_textures: 0,
             max_vertex_textures: 0,
             max_texture_size: 0,
             max_cube_texture_size: 0,
+            max_3d_texture_size: 0,
+            max_array_texture_layers: 0,
             max_render_buffer_size: 0,
+            max_samples: 0,
+            max_integer_samples: 0,
+            max_depth_texture_samples: 0,
+            max_color_attachments: 0,
+            max_draw_buffers: 0,
 
             max_uniforms: 0,
             max_varyings: 0,
@@ -117,6 +305,7 @@ impl Context {
             viewport_y: 0,
             viewport_width: 1,
             viewport_height: 1,
+            viewport_valid: true,
 
             blending: Blending::Default,
             cull_face: CullFace::Back,
@@ -125,6 +314,8 @@ impl Context {
             blending_disabled: true,
             cull_face_disabled: true,
             depth_test_disabled: true,
+            stencil_test_disabled: true,
+            scissor_test_disabled: true,
 
             clear_depth: 1f64,
             clear_stencil: 0isize,
@@ -134,10 +325,15 @@ impl Context {
             depth_range_far: 1f64,
             line_width: 1f32,
 
+            stencil: Stencil::none(),
+            scissor: None,
+            color_mask: [true, true, true, true],
+
             current_buffer: 0,
             current_buffer_kind: 0,
             current_vertex_array: 0,
             current_framebuffer: 0,
+            current_read_framebuffer: 0,
             current_renderbuffer: 0,
 
             current_program: 0,
@@ -146,11 +342,22 @@ impl Context {
             texture_index: 0,
             current_texture_index: -1,
             current_texture: 0,
+            current_texture_kind: 0,
+
+            current_samplers: Vec::new(),
+
+            state_stack: Vec::new(),
+            verify_after_draw: false,
+
+            debug_callback: None,
+            debug_group_depth: 0,
         }
     }
 
     #[inline(always)]
     pub fn version(&self) -> &String { &self.version }
+    #[inline(always)]
+    pub fn is_es(&self) -> bool { self.is_es }
 
     #[inline(always)]
     pub fn major(&self) -> usize { self.major }
@@ -179,7 +386,21 @@ impl Context {
     #[inline(always)]
     pub fn max_cube_texture_size(&self) -> usize { self.max_cube_texture_size }
     #[inline(always)]
+    pub fn max_3d_texture_size(&self) -> usize { self.max_3d_texture_size }
+    #[inline(always)]
+    pub fn max_array_texture_layers(&self) -> usize { self.max_array_texture_layers }
+    #[inline(always)]
     pub fn max_render_buffer_size(&self) -> usize { self.max_render_buffer_size }
+    #[inline(always)]
+    pub fn max_samples(&self) -> usize { self.max_samples }
+    #[inline(always)]
+    pub fn max_integer_samples(&self) -> usize { self.max_integer_samples }
+    #[inline(always)]
+    pub fn max_depth_texture_samples(&self) -> usize { self.max_depth_texture_samples }
+    #[inline(always)]
+    pub fn max_color_attachments(&self) -> usize { self.max_color_attachments }
+    #[inline(always)]
+    pub fn max_draw_buffers(&self) -> usize { self.max_draw_buffers }
 
     #[inline(always)]
     pub fn max_uniforms(&self) -> usize { self.max_uniforms }
@@ -202,6 +423,10 @@ impl Context {
     pub fn viewport_width(&self) -> usize { self.viewport_width }
     #[inline(always)]
     pub fn viewport_height(&self) -> usize { self.viewport_height }
+    #[inline(always)]
+    pub fn viewport_valid(&self) -> bool { self.viewport_valid }
+    #[inline(always)]
+    pub fn clear_color_valid(&self) -> bool { self.clear_color_valid }
 
     #[inline(always)]
     pub fn blending(&self) -> Blending { self.blending }
@@ -216,6 +441,10 @@ impl Context {
     pub fn cull_face_disabled(&self) -> bool { self.cull_face_disabled }
     #[inline(always)]
     pub fn depth_test_disabled(&self) -> bool { self.depth_test_disabled }
+    #[inline(always)]
+    pub fn stencil_test_disabled(&self) -> bool { self.stencil_test_disabled }
+    #[inline(always)]
+    pub fn scissor_test_disabled(&self) -> bool { self.scissor_test_disabled }
 
     #[inline(always)]
     pub fn clear_depth(&self) -> f64 { self.clear_depth }
@@ -231,6 +460,29 @@ impl Context {
     #[inline(always)]
     pub fn line_width(&self) -> f32 { self.line_width }
 
+    #[inline(always)]
+    pub fn stencil(&self) -> &Stencil { &self.stencil }
+    #[inline(always)]
+    pub fn scissor(&self) -> Option<[usize; 4]> { self.scissor }
+    #[inline(always)]
+    pub fn color_mask(&self) -> &[bool; 4] { &self.color_mask }
+
+    #[inline]
+    pub fn render_state(&self) -> RenderState {
+        let mut render_state = RenderState::new();
+        render_state
+            .set_blending(self.blending)
+            .set_cull_face(self.cull_face)
+            .set_depth_func(self.depth_func)
+            .set_depth_write(self.depth_write)
+            .set_depth_range(self.depth_range_near, self.depth_range_far)
+            .set_line_width(self.line_width)
+            .set_stencil(self.stencil)
+            .set_scissor(self.scissor)
+            .set_color_mask(&self.color_mask);
+        render_state
+    }
+
     #[inline(always)]
     pub fn current_buffer(&self) -> GLuint { self.current_buffer }
     #[inline(always)]
@@ -241,6 +493,8 @@ impl Context {
     #[inline(always)]
     pub fn current_framebuffer(&self) -> GLuint { self.current_framebuffer }
     #[inline(always)]
+    pub fn current_read_framebuffer(&self) -> GLuint { self.current_read_framebuffer }
+    #[inline(always)]
     pub fn current_renderbuffer(&self) -> GLuint { self.current_renderbuffer }
 
     #[inline(always)]
@@ -254,6 +508,16 @@ impl Context {
     pub fn current_texture_index(&self) -> GLint { self.current_texture_index }
     #[inline(always)]
     pub fn current_texture(&self) -> GLuint { self.current_texture }
+    #[inline(always)]
+    pub fn current_texture_kind(&self) -> GLenum { self.current_texture_kind }
+
+    #[inline(always)]
+    pub fn current_samplers(&self) -> &[GLuint] { &self.current_samplers }
+
+    #[inline(always)]
+    pub fn state_depth(&self) -> usize { self.state_stack.len() }
+    #[inline(always)]
+    pub fn verify_after_draw(&self) -> bool { self.verify_after_draw }
 
 
     #[inline(always)]
@@ -265,6 +529,7 @@ impl Context {
     pub fn reset(&mut self) -> &mut Self {
 
         self.version.clear();
+        self.is_es = false;
 
         self.extenstions.clear();
 
@@ -272,13 +537,21 @@ impl Context {
         self.clear_color[1] = 0f32;
         self.clear_color[2] = 0f32;
         self.clear_color[3] = 1f32;
+        self.clear_color_valid = true;
 
         self.max_anisotropy = 0;
         self.max_textures = 0;
         self.max_vertex_textures = 0;
         self.max_texture_size = 0;
         self.max_cube_texture_size = 0;
+        self.max_3d_texture_size = 0;
+        self.max_array_texture_layers = 0;
         self.max_render_buffer_size = 0;
+        self.max_samples = 0;
+        self.max_integer_samples = 0;
+        self.max_depth_texture_samples = 0;
+        self.max_color_attachments = 0;
+        self.max_draw_buffers = 0;
 
         self.max_uniforms = 0;
         self.max_varyings = 0;
@@ -292,6 +565,7 @@ impl Context {
         self.viewport_y = 0;
         self.viewport_width = 1;
         self.viewport_height = 1;
+        self.viewport_valid = true;
 
         self.blending = Blending::Default;
         self.cull_face = CullFace::Back;
@@ -300,6 +574,8 @@ impl Context {
         self.blending_disabled = true;
         self.cull_face_disabled = true;
         self.depth_test_disabled = true;
+        self.stencil_test_disabled = true;
+        self.scissor_test_disabled = true;
 
         self.clear_depth = 1f64;
         self.clear_stencil = 0isize;
@@ -309,10 +585,15 @@ impl Context {
         self.depth_range_far = 1f64;
         self.line_width = 1f32;
 
+        self.stencil = Stencil::none();
+        self.scissor = None;
+        self.color_mask = [true, true, true, true];
+
         self.current_buffer = 0;
         self.current_buffer_kind = 0;
         self.current_vertex_array = 0;
         self.current_framebuffer = 0;
+        self.current_read_framebuffer = 0;
         self.current_renderbuffer = 0;
 
         self.current_program = 0;
@@ -321,6 +602,11 @@ impl Context {
         self.texture_index = 0;
         self.current_texture_index = -1;
         self.current_texture = 0;
+        self.current_texture_kind = 0;
+
+        self.current_samplers.clear();
+
+        self.state_stack.clear();
 
         self.gl_info();
         self.gl_reset();
@@ -335,11 +621,13 @@ impl Context {
         self.clear_color[1] = 0f32;
         self.clear_color[2] = 0f32;
         self.clear_color[3] = 1f32;
+        self.clear_color_valid = false;
 
         self.viewport_x = 0;
         self.viewport_y = 0;
         self.viewport_width = 1;
         self.viewport_height = 1;
+        self.viewport_valid = false;
 
         self
     }
@@ -354,6 +642,7 @@ impl Context {
         self.disable_attributes();
 
         self.set_viewport_unchecked(self.viewport_x, self.viewport_y, self.viewport_width, self.viewport_height);
+        self.viewport_valid = true;
 
         self.set_clear_depth_unchecked(self.clear_depth);
         self.set_clear_stencil_unchecked(self.clear_stencil);
@@ -369,20 +658,30 @@ impl Context {
         self.set_cull_face_unchecked(cull_face);
         self.set_depth_func_unchecked(depth_func);
 
+        let stencil = self.stencil;
+        let scissor = self.scissor;
+        self.set_stencil_unchecked(&stencil);
+        self.set_scissor_unchecked(scissor);
+        self.set_color_mask_unchecked(&self.color_mask);
+
         self.set_clear_color_unchecked(&self.clear_color);
+        self.clear_color_valid = true;
         self.clear(true, true, true);
 
+        gl_check!("Context::gl_reset");
         self
     }
 
     #[inline(always)]
     pub fn set_viewport_unchecked(&self, x: usize, y: usize, width: usize, height: usize) -> &Self {
         unsafe { gl::Viewport(x as GLint, y as GLint, width as GLsizei, height as GLsizei); }
+        gl_check!("Context::set_viewport_unchecked");
         self
     }
     #[inline(always)]
     pub fn set_viewport(&mut self, x: usize, y: usize, width: usize, height: usize) -> &mut Self {
         if
+            !self.viewport_valid ||
             self.viewport_x != x ||
             self.viewport_y != y ||
             self.viewport_width != width ||
@@ -392,6 +691,7 @@ impl Context {
             self.viewport_y = y;
             self.viewport_width = width;
             self.viewport_height = height;
+            self.viewport_valid = true;
             self.set_viewport_unchecked(x, y, width, height);
         }
         self
@@ -400,6 +700,7 @@ impl Context {
     #[inline(always)]
     pub fn set_clear_depth_unchecked(&self, clear_depth: f64) -> &Self {
         unsafe { gl::ClearDepth(clear_depth); }
+        gl_check!("Context::set_clear_depth_unchecked");
         self
     }
     #[inline(always)]
@@ -414,6 +715,7 @@ impl Context {
     #[inline(always)]
     pub fn set_clear_stencil_unchecked(&self, clear_stencil: isize) -> &Self {
         unsafe { gl::ClearStencil(clear_stencil as GLint); }
+        gl_check!("Context::set_clear_stencil_unchecked");
         self
     }
     #[inline(always)]
@@ -428,6 +730,7 @@ impl Context {
     #[inline(always)]
     pub fn set_depth_write_unchecked(&self, depth_write: bool) -> &Self {
         unsafe { gl::DepthMask(if depth_write {gl::TRUE} else {gl::FALSE}); }
+        gl_check!("Context::set_depth_write_unchecked");
         self
     }
     #[inline(always)]
@@ -442,11 +745,12 @@ impl Context {
     #[inline(always)]
     pub fn set_depth_range_unchecked(&self, near: f64, far: f64) -> &Self {
         unsafe { gl::DepthRange(near, far); }
+        gl_check!("Context::set_depth_range_unchecked");
         self
     }
     #[inline(always)]
     pub fn set_depth_range(&mut self, near: f64, far: f64) -> &mut Self {
-        if near != near && far != far {
+        if self.depth_range_near != near || self.depth_range_far != far {
             self.depth_range_near = near;
             self.depth_range_far = far;
             self.set_depth_range_unchecked(near, far);
@@ -457,6 +761,7 @@ impl Context {
     #[inline(always)]
     pub fn set_line_width_unchecked(&self, line_width: f32) -> &Self {
         unsafe { gl::LineWidth(line_width as GLfloat); }
+        gl_check!("Context::set_line_width_unchecked");
         self
     }
     #[inline(always)]
@@ -511,6 +816,7 @@ impl Context {
                 self.blending_disabled = true;
             },
         }
+        gl_check!("Context::set_blending_unchecked");
         self
     }
     #[inline(always)]
@@ -549,6 +855,7 @@ impl Context {
                 unsafe { gl::Disable(gl::CULL_FACE); }
             },
         }
+        gl_check!("Context::set_cull_face_unchecked");
         self
     }
     #[inline(always)]
@@ -607,6 +914,7 @@ impl Context {
                 unsafe { gl::Disable(gl::DEPTH_TEST); }
             },
         }
+        gl_check!("Context::set_depth_func_unchecked");
         self
     }
     #[inline(always)]
@@ -618,18 +926,145 @@ impl Context {
         self
     }
 
+    #[inline(always)]
+    fn enable_stencil_test(&mut self) {
+        if self.stencil_test_disabled {
+            unsafe { gl::Enable(gl::STENCIL_TEST); }
+            self.stencil_test_disabled = false;
+        }
+    }
+    #[inline]
+    pub fn set_stencil_unchecked(&mut self, stencil: &Stencil) -> &mut Self {
+        match stencil.func() {
+            Depth::None => {
+                self.stencil_test_disabled = true;
+                unsafe { gl::Disable(gl::STENCIL_TEST); }
+            },
+            func => {
+                self.enable_stencil_test();
+                unsafe {
+                    gl::StencilFunc(func.to_gl(), stencil.reference(), stencil.read_mask());
+                    gl::StencilOp(stencil.fail().to_gl(), stencil.depth_fail().to_gl(), stencil.pass().to_gl());
+                }
+            },
+        }
+        unsafe { gl::StencilMask(stencil.write_mask()); }
+        gl_check!("Context::set_stencil_unchecked");
+        self
+    }
+    #[inline(always)]
+    pub fn set_stencil(&mut self, stencil: &Stencil) -> &mut Self {
+        if &self.stencil != stencil {
+            self.stencil = *stencil;
+            self.set_stencil_unchecked(stencil);
+        }
+        self
+    }
+
+    #[inline(always)]
+    fn enable_scissor_test(&mut self) {
+        if self.scissor_test_disabled {
+            unsafe { gl::Enable(gl::SCISSOR_TEST); }
+            self.scissor_test_disabled = false;
+        }
+    }
+    #[inline]
+    pub fn set_scissor_unchecked(&mut self, scissor: Option<[usize; 4]>) -> &mut Self {
+        match scissor {
+            Some(rect) => {
+                self.enable_scissor_test();
+                unsafe { gl::Scissor(rect[0] as GLint, rect[1] as GLint, rect[2] as GLsizei, rect[3] as GLsizei); }
+            },
+            None => {
+                self.scissor_test_disabled = true;
+                unsafe { gl::Disable(gl::SCISSOR_TEST); }
+            },
+        }
+        gl_check!("Context::set_scissor_unchecked");
+        self
+    }
+    #[inline(always)]
+    pub fn set_scissor(&mut self, scissor: Option<[usize; 4]>) -> &mut Self {
+        if self.scissor != scissor {
+            self.scissor = scissor;
+            self.set_scissor_unchecked(scissor);
+        }
+        self
+    }
+
+    #[inline(always)]
+    pub fn set_color_mask_unchecked(&self, color_mask: &[bool; 4]) -> &Self {
+        unsafe {
+            gl::ColorMask(
+                if color_mask[0] {gl::TRUE} else {gl::FALSE},
+                if color_mask[1] {gl::TRUE} else {gl::FALSE},
+                if color_mask[2] {gl::TRUE} else {gl::FALSE},
+                if color_mask[3] {gl::TRUE} else {gl::FALSE}
+            );
+        }
+        gl_check!("Context::set_color_mask_unchecked");
+        self
+    }
+    #[inline(always)]
+    pub fn set_color_mask(&mut self, color_mask: &[bool; 4]) -> &mut Self {
+        if &self.color_mask != color_mask {
+            self.color_mask = *color_mask;
+            self.set_color_mask_unchecked(color_mask);
+        }
+        self
+    }
+
+    #[inline]
+    pub fn apply_render_state(&mut self, render_state: &RenderState) -> &mut Self {
+        let diff = render_state.diff(&self.render_state());
+
+        if diff.blending() {
+            self.set_blending(render_state.blending());
+        }
+        if diff.cull_face() {
+            self.set_cull_face(render_state.cull_face());
+        }
+        if diff.depth_func() {
+            self.set_depth_func(render_state.depth_func());
+        }
+
+        if diff.depth_write() {
+            self.set_depth_write(render_state.depth_write());
+        }
+        if diff.depth_range() {
+            self.set_depth_range(render_state.depth_range_near(), render_state.depth_range_far());
+        }
+        if diff.line_width() {
+            self.set_line_width(render_state.line_width());
+        }
+
+        if diff.stencil() {
+            self.set_stencil(render_state.stencil());
+        }
+        if diff.scissor() {
+            self.set_scissor(render_state.scissor());
+        }
+        if diff.color_mask() {
+            self.set_color_mask(render_state.color_mask());
+        }
+
+        self
+    }
+
     #[inline(always)]
     pub fn set_clear_color_unchecked(&self, color: &[f32; 4]) -> &Self {
         unsafe { gl::ClearColor(color[0], color[1], color[2], color[3]); }
+        gl_check!("Context::set_clear_color_unchecked");
         self
     }
     #[inline(always)]
     pub fn set_clear_color(&mut self, color: &[f32; 4]) -> &mut Self {
-        if &self.clear_color != color {
+        if !self.clear_color_valid || &self.clear_color != color {
             self.clear_color[0] = color[0];
             self.clear_color[1] = color[1];
             self.clear_color[2] = color[2];
             self.clear_color[3] = color[3];
+            self.clear_color_valid = true;
             self.set_clear_color_unchecked(color);
         }
         self
@@ -655,6 +1090,7 @@ impl Context {
     #[inline(always)]
     pub fn clear_bits(&mut self, bits: GLenum) -> &mut Self {
         unsafe { gl::Clear(bits); }
+        gl_check!("Context::clear_bits");
         self
     }
 
@@ -664,6 +1100,7 @@ impl Context {
 
         if force || !*value {
             unsafe { gl::EnableVertexAttribArray(index as GLuint); }
+            gl_check!("Context::enable_attribute");
             *value = true;
             true
         } else {
@@ -676,6 +1113,7 @@ impl Context {
 
         if *value {
             unsafe { gl::DisableVertexAttribArray(index as GLuint); }
+            gl_check!("Context::disable_attribute");
             *value = false;
             true
         } else {
@@ -695,6 +1133,7 @@ impl Context {
             }
             index += 1;
         }
+        gl_check!("Context::disable_attributes");
     }
 
     #[inline(always)]
@@ -705,6 +1144,7 @@ impl Context {
         if force || self.current_buffer != id {
             self.disable_attributes();
             unsafe { gl::BindBuffer(kind, id); }
+            gl_check!("Context::set_buffer");
             self.current_buffer = id;
             self.current_buffer_kind = kind;
             true
@@ -719,6 +1159,7 @@ impl Context {
             unsafe {
                 gl::BindBuffer(self.current_buffer_kind, 0 as GLuint);
             }
+            gl_check!("Context::remove_buffer");
             self.current_buffer = 0;
             self.current_buffer_kind = 0;
             true
@@ -739,9 +1180,10 @@ impl Context {
                     kind,
                     gl::FALSE,
                     stride,
-                    mem::transmute(offset as usize)
+                    offset as usize as *const GLvoid
                 );
             }
+            gl_check!("Context::set_attrib_pointer");
             true
         } else {
             false
@@ -754,6 +1196,7 @@ impl Context {
 
         if force || self.current_vertex_array != id {
             unsafe { gl::BindVertexArray(id); }
+            gl_check!("Context::set_vertex_array");
             self.current_vertex_array = id;
             true
         } else {
@@ -764,6 +1207,7 @@ impl Context {
     pub fn remove_vertex_array(&mut self, force: bool) -> bool {
         if force || self.current_vertex_array != 0 {
             unsafe { gl::BindVertexArray(0); }
+            gl_check!("Context::remove_vertex_array");
             self.current_vertex_array = 0;
             true
         } else {
@@ -775,9 +1219,11 @@ impl Context {
     pub fn set_framebuffer(&mut self, framebuffer: &Framebuffer, force: bool) -> bool {
         let id = framebuffer.id();
 
-        if force || self.current_framebuffer != id {
+        if force || self.current_framebuffer != id || self.current_read_framebuffer != id {
             unsafe { gl::BindFramebuffer(gl::FRAMEBUFFER, id); }
+            gl_check!("Context::set_framebuffer");
             self.current_framebuffer = id;
+            self.current_read_framebuffer = id;
             self.soft_reset();
             true
         } else {
@@ -786,9 +1232,45 @@ impl Context {
     }
     #[inline(always)]
     pub fn remove_framebuffer(&mut self, force: bool) -> bool {
-        if force || self.current_framebuffer != 0 {
+        if force || self.current_framebuffer != 0 || self.current_read_framebuffer != 0 {
             unsafe { gl::BindFramebuffer(gl::FRAMEBUFFER, 0); }
+            gl_check!("Context::remove_framebuffer");
             self.current_framebuffer = 0;
+            self.current_read_framebuffer = 0;
+            self.soft_reset();
+            true
+        } else {
+            false
+        }
+    }
+
+    #[inline(always)]
+    pub fn set_read_framebuffer(&mut self, framebuffer: Option<&Framebuffer>, force: bool) -> bool {
+        let id = match framebuffer {
+            Some(framebuffer) => framebuffer.id(),
+            None => 0,
+        };
+
+        if force || self.current_read_framebuffer != id {
+            unsafe { gl::BindFramebuffer(gl::READ_FRAMEBUFFER, id); }
+            gl_check!("Context::set_read_framebuffer");
+            self.current_read_framebuffer = id;
+            true
+        } else {
+            false
+        }
+    }
+    #[inline(always)]
+    pub fn set_draw_framebuffer(&mut self, framebuffer: Option<&Framebuffer>, force: bool) -> bool {
+        let id = match framebuffer {
+            Some(framebuffer) => framebuffer.id(),
+            None => 0,
+        };
+
+        if force || self.current_framebuffer != id {
+            unsafe { gl::BindFramebuffer(gl::DRAW_FRAMEBUFFER, id); }
+            gl_check!("Context::set_draw_framebuffer");
+            self.current_framebuffer = id;
             self.soft_reset();
             true
         } else {
@@ -796,12 +1278,64 @@ impl Context {
         }
     }
 
+    #[inline]
+    pub fn blit_framebuffer(
+        &mut self,
+        src: Option<&Framebuffer>,
+        dst: Option<&Framebuffer>,
+        src_rect: &[usize; 4],
+        dst_rect: &[usize; 4],
+        mask: GLbitfield,
+        filter: FilterMode
+    ) -> &mut Self {
+        if filter != FilterMode::None && (mask & (gl::DEPTH_BUFFER_BIT | gl::STENCIL_BUFFER_BIT)) != 0 {
+            panic!("Context::blit_framebuffer can only use linear filtering for color buffers");
+        }
+
+        self.set_read_framebuffer(src, false);
+        self.set_draw_framebuffer(dst, false);
+
+        unsafe {
+            gl::BlitFramebuffer(
+                src_rect[0] as GLint, src_rect[1] as GLint,
+                (src_rect[0] + src_rect[2]) as GLint, (src_rect[1] + src_rect[3]) as GLint,
+                dst_rect[0] as GLint, dst_rect[1] as GLint,
+                (dst_rect[0] + dst_rect[2]) as GLint, (dst_rect[1] + dst_rect[3]) as GLint,
+                mask, if filter == FilterMode::None {gl::NEAREST} else {gl::LINEAR}
+            );
+        }
+        gl_check!("Context::blit_framebuffer");
+
+        self
+    }
+
+    #[inline(always)]
+    pub fn has_multisample_textures(&self) -> bool {
+        let core = if self.is_es {
+            self.major > 3 || (self.major == 3 && self.minor >= 1)
+        } else {
+            self.major > 3 || (self.major == 3 && self.minor >= 2)
+        };
+        core || self.has_extenstion("GL_ARB_texture_multisample")
+    }
+
+    #[inline(always)]
+    pub fn has_invalidate_framebuffer(&self) -> bool {
+        let core = if self.is_es {
+            self.major >= 3
+        } else {
+            self.major > 4 || (self.major == 4 && self.minor >= 3)
+        };
+        (core || self.has_extenstion("GL_ARB_invalidate_subdata")) && gl::InvalidateFramebuffer::is_loaded()
+    }
+
     #[inline(always)]
     pub fn set_renderbuffer(&mut self, renderbuffer: &Renderbuffer, force: bool) -> bool {
         let id = renderbuffer.id();
 
         if force || self.current_renderbuffer != id {
             unsafe { gl::BindRenderbuffer(gl::RENDERBUFFER, id); }
+            gl_check!("Context::set_renderbuffer");
             self.current_renderbuffer = id;
             true
         } else {
@@ -812,6 +1346,7 @@ impl Context {
     pub fn remove_renderbuffer(&mut self, force: bool) -> bool {
         if force || self.current_renderbuffer != 0 {
             unsafe { gl::BindRenderbuffer(gl::RENDERBUFFER, 0); }
+            gl_check!("Context::remove_renderbuffer");
             self.current_renderbuffer = 0;
             true
         } else {
@@ -822,6 +1357,7 @@ impl Context {
     #[inline(always)]
     pub fn set_texture(&mut self, location: GLint, texture: &Texture, force: bool) -> bool {
         let id = texture.id();
+        let kind = texture.kind();
         let index = self.texture_index;
         let current_texture_index = self.current_texture_index;
 
@@ -835,9 +1371,11 @@ impl Context {
                 unsafe { gl::ActiveTexture(gl::TEXTURE0 + index); }
                 unsafe { gl::Uniform1i(location, index as GLint); }
             }
-            unsafe { gl::BindTexture(gl::TEXTURE_2D, id); }
+            unsafe { gl::BindTexture(kind, id); }
+            gl_check!("Context::set_texture");
 
             self.current_texture = id;
+            self.current_texture_kind = kind;
 
             true
         } else {
@@ -849,12 +1387,81 @@ impl Context {
         if force || self.current_texture != 0 {
             self.texture_index = 0;
             self.current_texture_index = -1;
-            unsafe { gl::BindTexture(gl::TEXTURE_2D, 0 as GLuint); }
+            unsafe {
+                gl::BindTexture(
+                    if self.current_texture_kind != 0 {self.current_texture_kind} else {gl::TEXTURE_2D},
+                    0 as GLuint
+                );
+            }
+            gl_check!("Context::remove_texture");
+            self.current_texture = 0;
+            self.current_texture_kind = 0;
+            true
+        } else {
+            false
+        }
+    }
+
+    #[inline(always)]
+    pub fn has_samplers(&self) -> bool {
+        let core = if self.is_es {
+            self.major >= 3
+        } else {
+            self.major > 3 || (self.major == 3 && self.minor >= 3)
+        };
+        (core || self.has_extenstion("GL_ARB_sampler_objects")) && gl::GenSamplers::is_loaded()
+    }
+    #[inline(always)]
+    pub fn has_border_clamp(&self) -> bool {
+        !self.is_es ||
+            self.major > 3 || (self.major == 3 && self.minor >= 2) ||
+            self.has_extenstion("GL_EXT_texture_border_clamp") ||
+            self.has_extenstion("GL_OES_texture_border_clamp")
+    }
+
+    #[inline(always)]
+    pub fn set_sampler(&mut self, unit: usize, sampler: &Sampler, force: bool) -> bool {
+        self.check_sampler_unit(unit);
+
+        let id = sampler.id();
+        let ref mut value = self.current_samplers[unit];
+
+        if force || *value != id {
+            unsafe { gl::BindSampler(unit as GLuint, id); }
+            gl_check!("Context::set_sampler");
+            *value = id;
+            true
+        } else {
+            false
+        }
+    }
+    #[inline(always)]
+    pub fn remove_sampler(&mut self, unit: usize, force: bool) -> bool {
+        if !self.has_samplers() {
+            return false;
+        }
+        self.check_sampler_unit(unit);
+
+        let ref mut value = self.current_samplers[unit];
+
+        if force || *value != 0 {
+            unsafe { gl::BindSampler(unit as GLuint, 0); }
+            gl_check!("Context::remove_sampler");
+            *value = 0;
             true
         } else {
             false
         }
     }
+    #[inline(always)]
+    fn check_sampler_unit(&self, unit: usize) {
+        if unit >= self.current_samplers.len() {
+            panic!(
+                "Sampler unit {:?} exceeds max texture units {:?}, was the Context initialized?",
+                unit, self.current_samplers.len()
+            );
+        }
+    }
 
     #[inline(always)]
     pub fn set_program(&mut self, program: &Program, force: bool) -> bool {
@@ -864,6 +1471,7 @@ impl Context {
             self.current_program = id;
             self.force = true;
             unsafe { gl::UseProgram(id); }
+            gl_check!("Context::set_program");
         } else {
             if self.texture_index != 0 || self.current_texture_index != -1 {
                 self.force = true;
@@ -883,6 +1491,7 @@ impl Context {
             self.current_program = 0;
             self.force = true;
             unsafe { gl::UseProgram(0 as GLuint); }
+            gl_check!("Context::remove_program");
         } else {
             if self.texture_index != 0 || self.current_texture_index != -1 {
                 self.force = true;
@@ -897,6 +1506,179 @@ impl Context {
         true
     }
 
+    #[inline]
+    pub fn state(&self) -> ContextState {
+        ContextState {
+            render_state: self.render_state(),
+
+            clear_color: self.clear_color,
+            clear_color_valid: self.clear_color_valid,
+            clear_depth: self.clear_depth,
+            clear_stencil: self.clear_stencil,
+
+            enabled_attributes: self.enabled_attributes.clone(),
+
+            viewport_x: self.viewport_x,
+            viewport_y: self.viewport_y,
+            viewport_width: self.viewport_width,
+            viewport_height: self.viewport_height,
+            viewport_valid: self.viewport_valid,
+
+            current_buffer: self.current_buffer,
+            current_buffer_kind: self.current_buffer_kind,
+            current_vertex_array: self.current_vertex_array,
+            current_framebuffer: self.current_framebuffer,
+            current_read_framebuffer: self.current_read_framebuffer,
+            current_renderbuffer: self.current_renderbuffer,
+
+            current_program: self.current_program,
+
+            texture_index: self.texture_index,
+            current_texture_index: self.current_texture_index,
+            current_texture: self.current_texture,
+            current_texture_kind: self.current_texture_kind,
+
+            current_samplers: self.current_samplers.clone(),
+        }
+    }
+
+    #[inline]
+    pub fn set_state(&mut self, state: &ContextState) -> &mut Self {
+        if
+            self.current_framebuffer != state.current_framebuffer ||
+            self.current_read_framebuffer != state.current_read_framebuffer
+        {
+            unsafe {
+                if state.current_framebuffer == state.current_read_framebuffer {
+                    gl::BindFramebuffer(gl::FRAMEBUFFER, state.current_framebuffer);
+                } else {
+                    gl::BindFramebuffer(gl::DRAW_FRAMEBUFFER, state.current_framebuffer);
+                    gl::BindFramebuffer(gl::READ_FRAMEBUFFER, state.current_read_framebuffer);
+                }
+            }
+            self.current_framebuffer = state.current_framebuffer;
+            self.current_read_framebuffer = state.current_read_framebuffer;
+            gl_check!("Context::set_state: framebuffer");
+        }
+        if self.current_renderbuffer != state.current_renderbuffer {
+            unsafe { gl::BindRenderbuffer(gl::RENDERBUFFER, state.current_renderbuffer); }
+            self.current_renderbuffer = state.current_renderbuffer;
+            gl_check!("Context::set_state: renderbuffer");
+        }
+        if self.current_program != state.current_program {
+            unsafe { gl::UseProgram(state.current_program); }
+            self.current_program = state.current_program;
+            self.force = true;
+            gl_check!("Context::set_state: program");
+        }
+        if self.current_vertex_array != state.current_vertex_array {
+            unsafe { gl::BindVertexArray(state.current_vertex_array); }
+            self.current_vertex_array = state.current_vertex_array;
+            gl_check!("Context::set_state: vertex array");
+        }
+        if
+            self.current_buffer != state.current_buffer ||
+            self.current_buffer_kind != state.current_buffer_kind
+        {
+            if state.current_buffer_kind != 0 {
+                unsafe { gl::BindBuffer(state.current_buffer_kind, state.current_buffer); }
+            } else if self.current_buffer_kind != 0 {
+                unsafe { gl::BindBuffer(self.current_buffer_kind, 0); }
+            }
+            self.current_buffer = state.current_buffer;
+            self.current_buffer_kind = state.current_buffer_kind;
+            gl_check!("Context::set_state: buffer");
+        }
+
+        for index in 0..self.enabled_attributes.len() {
+            let enabled = match state.enabled_attributes.get(index) {
+                Some(enabled) => *enabled,
+                None => false,
+            };
+            if enabled {
+                self.enable_attribute(index, false);
+            } else {
+                self.disable_attribute(index);
+            }
+        }
+
+        if
+            self.current_texture != state.current_texture ||
+            self.current_texture_kind != state.current_texture_kind ||
+            self.current_texture_index != state.current_texture_index
+        {
+            let index = if state.current_texture_index < 0 {0} else {state.current_texture_index as GLuint};
+            unsafe {
+                gl::ActiveTexture(gl::TEXTURE0 + index);
+                if self.current_texture_kind != 0 && self.current_texture_kind != state.current_texture_kind {
+                    gl::BindTexture(self.current_texture_kind, 0);
+                }
+                if state.current_texture_kind != 0 {
+                    gl::BindTexture(state.current_texture_kind, state.current_texture);
+                } else {
+                    gl::BindTexture(gl::TEXTURE_2D, 0);
+                }
+            }
+            gl_check!("Context::set_state: texture");
+        }
+        self.texture_index = state.texture_index;
+        self.current_texture_index = state.current_texture_index;
+        self.current_texture = state.current_texture;
+        self.current_texture_kind = state.current_texture_kind;
+
+        for unit in 0..self.current_samplers.len() {
+            let id = match state.current_samplers.get(unit) {
+                Some(id) => *id,
+                None => 0,
+            };
+            if self.current_samplers[unit] != id {
+                unsafe { gl::BindSampler(unit as GLuint, id); }
+                self.current_samplers[unit] = id;
+                gl_check!("Context::set_state: sampler");
+            }
+        }
+
+        // an invalid snapshot holds placeholder values, the real GL state at
+        // push time is unknown so leave the cache invalid instead
+        if state.viewport_valid {
+            self.set_viewport(state.viewport_x, state.viewport_y, state.viewport_width, state.viewport_height);
+        } else {
+            self.viewport_valid = false;
+        }
+        if state.clear_color_valid {
+            self.set_clear_color(&state.clear_color);
+        } else {
+            self.clear_color_valid = false;
+        }
+        self.set_clear_depth(state.clear_depth);
+        self.set_clear_stencil(state.clear_stencil);
+        self.apply_render_state(&state.render_state);
+
+        gl_check!("Context::set_state: render state");
+        self
+    }
+
+    #[inline]
+    pub fn push_state(&mut self) -> &mut Self {
+        let state = self.state();
+        self.state_stack.push(state);
+        self
+    }
+    #[inline]
+    pub fn pop_state(&mut self) -> &mut Self {
+        match self.state_stack.pop() {
+            Some(state) => self.set_state(&state),
+            None => panic!("pop_state called without a matching push_state"),
+        }
+    }
+    #[inline]
+    pub fn scoped_state(&mut self) -> StateGuard {
+        self.push_state();
+        StateGuard {
+            context: self,
+        }
+    }
+
     #[inline(always)]
     pub fn new_buffer(&self) -> Buffer {
         Buffer::new()
@@ -914,6 +1696,10 @@ impl Context {
         Renderbuffer::new()
     }
     #[inline(always)]
+    pub fn new_sampler(&self) -> Sampler {
+        Sampler::new(self)
+    }
+    #[inline(always)]
     pub fn new_texture(&self) -> Texture {
         Texture::new()
     }
@@ -927,6 +1713,8 @@ impl Context {
         unsafe {
             gl::DrawArrays(mode.to_gl(), first as GLint, count as GLsizei);
         }
+        gl_check!("Context::draw_arrays");
+        self.verify_draw();
         self
     }
     #[inline(always)]
@@ -936,12 +1724,41 @@ impl Context {
                 mode.to_gl(),
                 count as GLint,
                 kind.to_gl(),
-                mem::transmute(indices)
+                indices as *const T as *const GLvoid
             );
         }
+        gl_check!("Context::draw_elements");
+        self.verify_draw();
         self
     }
 
+    #[inline(always)]
+    pub fn set_verify_after_draw(&mut self, verify_after_draw: bool) -> &mut Self {
+        self.verify_after_draw = verify_after_draw;
+        self
+    }
+
+    #[cfg(debug_assertions)]
+    #[inline]
+    pub fn verify_state(&self) -> Vec<StateMismatch> {
+        verify::verify_state(self)
+    }
+
+    #[cfg(debug_assertions)]
+    #[inline]
+    fn verify_draw(&self) {
+        if self.verify_after_draw {
+            let mismatches = self.verify_state();
+
+            if !mismatches.is_empty() {
+                panic!("Context cache out of sync with GL after draw: {:?}", mismatches);
+            }
+        }
+    }
+    #[cfg(not(debug_assertions))]
+    #[inline(always)]
+    fn verify_draw(&self) {}
+
     #[inline(always)]
     pub fn has_extenstion(&self, string: &str) -> bool {
         match self.extenstions.iter().position(|e| e == string) {
@@ -954,6 +1771,128 @@ impl Context {
     pub fn error(&self) -> GLenum {
         unsafe { gl::GetError() }
     }
+    #[inline(always)]
+    pub fn errors(&self) -> Vec<GlError> {
+        error::drain_errors()
+    }
+
+    #[inline(always)]
+    pub fn has_debug_output(&self) -> bool {
+        let core = if self.is_es {
+            self.major > 3 || (self.major == 3 && self.minor >= 2)
+        } else {
+            self.major > 4 || (self.major == 4 && self.minor >= 3)
+        };
+        (core || self.has_extenstion("GL_KHR_debug")) && gl::DebugMessageCallback::is_loaded()
+    }
+    #[inline(always)]
+    pub fn has_debug_callback(&self) -> bool { self.debug_callback.is_some() }
+
+    #[inline]
+    pub fn set_debug_callback<F>(&mut self, synchronous: bool, callback: F) -> bool
+        where F: 'static + Send + FnMut(&DebugMessage)
+    {
+        if !self.has_debug_output() {
+            return false;
+        }
+
+        let mut callback: Box<DebugCallback> = Box::new(Box::new(callback));
+
+        unsafe {
+            gl::Enable(gl::DEBUG_OUTPUT);
+
+            if synchronous {
+                gl::Enable(gl::DEBUG_OUTPUT_SYNCHRONOUS);
+            } else {
+                gl::Disable(gl::DEBUG_OUTPUT_SYNCHRONOUS);
+            }
+
+            gl::DebugMessageCallback(
+                Some(debug::debug_message_callback),
+                (&mut *callback as *mut DebugCallback) as *const GLvoid
+            );
+        }
+        gl_check!("Context::set_debug_callback");
+
+        self.debug_callback = Some(callback);
+        true
+    }
+    #[inline]
+    pub fn remove_debug_callback(&mut self) -> bool {
+        if self.debug_callback.is_some() {
+            unsafe {
+                gl::DebugMessageCallback(None, 0 as *const GLvoid);
+                gl::Disable(gl::DEBUG_OUTPUT);
+            }
+            gl_check!("Context::remove_debug_callback");
+            self.debug_callback = None;
+            true
+        } else {
+            false
+        }
+    }
+
+    #[inline]
+    pub fn set_debug_filter(
+        &mut self, source: DebugSource, kind: DebugType, severity: DebugSeverity, enabled: bool
+    ) -> bool {
+        if self.has_debug_output() {
+            unsafe {
+                gl::DebugMessageControl(
+                    source.to_gl(),
+                    kind.to_gl(),
+                    severity.to_gl(),
+                    0,
+                    0 as *const GLuint,
+                    if enabled {gl::TRUE} else {gl::FALSE}
+                );
+            }
+            gl_check!("Context::set_debug_filter");
+            true
+        } else {
+            false
+        }
+    }
+
+    #[inline(always)]
+    pub fn debug_group_depth(&self) -> usize { self.debug_group_depth }
+
+    #[inline]
+    pub fn push_debug_group(&mut self, message: &str) -> bool {
+        if self.has_debug_output() {
+            unsafe {
+                gl::PushDebugGroup(
+                    gl::DEBUG_SOURCE_APPLICATION,
+                    0,
+                    message.len() as GLsizei,
+                    message.as_ptr() as *const GLchar
+                );
+            }
+            gl_check!("Context::push_debug_group");
+            self.debug_group_depth += 1;
+            true
+        } else {
+            false
+        }
+    }
+    #[inline]
+    pub fn pop_debug_group(&mut self) -> bool {
+        if self.debug_group_depth != 0 && self.has_debug_output() {
+            unsafe { gl::PopDebugGroup(); }
+            gl_check!("Context::pop_debug_group");
+            self.debug_group_depth -= 1;
+            true
+        } else {
+            false
+        }
+    }
+    #[inline]
+    pub fn scoped_debug_group(&mut self, message: &str) -> DebugGroup {
+        self.push_debug_group(message);
+        DebugGroup {
+            context: self,
+        }
+    }
 
     #[inline]
     fn gl_info(&mut self) {
@@ -1017,6 +1956,7 @@ impl Context {
         unsafe {
             let ptr = gl::GetString(gl::VERSION);
             string_from_ptr(ptr, &mut self.version);
+            self.is_es = self.version.starts_with("OpenGL ES");
 
             let (mut major, mut minor) = match
                 Regex::new(r"(\d+).(\d+)")
@@ -1066,10 +2006,47 @@ impl Context {
             gl::GetIntegerv(gl::MAX_CUBE_MAP_TEXTURE_SIZE, &mut max_cube_texture_size);
             self.max_cube_texture_size = max_cube_texture_size as usize;
 
+            if self.major >= 3 {
+                let mut max_3d_texture_size = 0;
+                gl::GetIntegerv(gl::MAX_3D_TEXTURE_SIZE, &mut max_3d_texture_size);
+                self.max_3d_texture_size = max_3d_texture_size as usize;
+
+                let mut max_array_texture_layers = 0;
+                gl::GetIntegerv(gl::MAX_ARRAY_TEXTURE_LAYERS, &mut max_array_texture_layers);
+                self.max_array_texture_layers = max_array_texture_layers as usize;
+            }
+
             let mut max_render_buffer_size = 0;
             gl::GetIntegerv(gl::MAX_RENDERBUFFER_SIZE, &mut max_render_buffer_size);
             self.max_render_buffer_size = max_render_buffer_size as usize;
 
+            if self.major >= 3 {
+                let mut max_samples = 0;
+                gl::GetIntegerv(gl::MAX_SAMPLES, &mut max_samples);
+                self.max_samples = max_samples as usize;
+
+                let mut max_color_attachments = 0;
+                gl::GetIntegerv(gl::MAX_COLOR_ATTACHMENTS, &mut max_color_attachments);
+                self.max_color_attachments = max_color_attachments as usize;
+
+                let mut max_draw_buffers = 0;
+                gl::GetIntegerv(gl::MAX_DRAW_BUFFERS, &mut max_draw_buffers);
+                self.max_draw_buffers = max_draw_buffers as usize;
+            } else {
+                self.max_color_attachments = 1;
+                self.max_draw_buffers = 1;
+            }
+
+            if self.has_multisample_textures() {
+                let mut max_integer_samples = 0;
+                gl::GetIntegerv(gl::MAX_INTEGER_SAMPLES, &mut max_integer_samples);
+                self.max_integer_samples = max_integer_samples as usize;
+
+                let mut max_depth_texture_samples = 0;
+                gl::GetIntegerv(gl::MAX_DEPTH_TEXTURE_SAMPLES, &mut max_depth_texture_samples);
+                self.max_depth_texture_samples = max_depth_texture_samples as usize;
+            }
+
             let mut vs_max_uniforms = 0;
             let mut fs_max_uniforms = 0;
             gl::GetIntegerv(gl::MAX_VERTEX_UNIFORM_VECTORS, &mut vs_max_uniforms);
@@ -1087,11 +2064,24 @@ impl Context {
             let mut max_attributes = 0;
             gl::GetIntegerv(gl::MAX_VERTEX_ATTRIBS, &mut max_attributes);
             self.max_attributes = max_attributes as usize;
+
+            if
+                self.has_extenstion("GL_EXT_texture_filter_anisotropic") ||
+                self.has_extenstion("GL_ARB_texture_filter_anisotropic") ||
+                (self.major == 4 && self.minor >= 6) || self.major > 4
+            {
+                let mut max_anisotropy = 0f32;
+                gl::GetFloatv(sampler::MAX_TEXTURE_MAX_ANISOTROPY, &mut max_anisotropy);
+                self.max_anisotropy = max_anisotropy as usize;
+            }
         }
 
         for _ in 0..self.max_attributes {
             self.enabled_attributes.push(false);
         }
+        for _ in 0..self.max_textures {
+            self.current_samplers.push(0);
+        }
     }
 }
 
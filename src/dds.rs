@@ -0,0 +1,246 @@
+use alloc::vec::Vec;
+
+use enums::CompressedFormat;
+use error::LoadError;
+use reader::{checked_offset, compressed_size, level_size, read_slice, read_u32};
+use texture::{mip_levels, CompressedImage};
+
+
+static DDS_MAGIC: [u8; 4] = [0x44, 0x44, 0x53, 0x20];
+
+static DDS_HEADER_SIZE: usize = 128;
+static DDS_DX10_HEADER_SIZE: usize = 20;
+
+static DDSD_MIPMAPCOUNT: u32 = 0x20000;
+static DDPF_FOURCC: u32 = 0x4;
+static DDSCAPS2_CUBEMAP: u32 = 0x200;
+static DDSCAPS2_VOLUME: u32 = 0x200000;
+static DDS_RESOURCE_MISC_TEXTURECUBE: u32 = 0x4;
+static DDS_DIMENSION_TEXTURE2D: u32 = 3;
+
+
+#[inline]
+pub fn parse(bytes: &[u8]) -> Result<CompressedImage, LoadError> {
+    if bytes.len() < 4 {
+        return Err(LoadError::Truncated);
+    }
+    if bytes[..4] != DDS_MAGIC {
+        return Err(LoadError::InvalidIdentifier);
+    }
+    if bytes.len() < DDS_HEADER_SIZE {
+        return Err(LoadError::Truncated);
+    }
+
+    let flags = read_u32(bytes, 8, false)?;
+    let height = read_u32(bytes, 12, false)? as usize;
+    let width = read_u32(bytes, 16, false)? as usize;
+    let mip_map_count = read_u32(bytes, 28, false)? as usize;
+    let pixel_flags = read_u32(bytes, 80, false)?;
+    let four_cc = read_u32(bytes, 84, false)?;
+    let caps2 = read_u32(bytes, 112, false)?;
+
+    if pixel_flags & DDPF_FOURCC == 0 {
+        return Err(LoadError::UnsupportedFormat(0));
+    }
+    if caps2 & (DDSCAPS2_CUBEMAP | DDSCAPS2_VOLUME) != 0 {
+        return Err(LoadError::UnsupportedLayout);
+    }
+
+    let mut offset = DDS_HEADER_SIZE;
+
+    let format = if four_cc == four_cc_code(b"DX10") {
+        let dxgi_format = read_u32(bytes, offset, false)?;
+        let dimension = read_u32(bytes, offset + 4, false)?;
+        let misc_flags = read_u32(bytes, offset + 8, false)?;
+        let array_size = read_u32(bytes, offset + 12, false)?;
+        offset += DDS_DX10_HEADER_SIZE;
+
+        if dimension != DDS_DIMENSION_TEXTURE2D || misc_flags & DDS_RESOURCE_MISC_TEXTURECUBE != 0 || array_size > 1 {
+            return Err(LoadError::UnsupportedLayout);
+        }
+        match dxgi_format_to_compressed(dxgi_format) {
+            Some(format) => format,
+            None => return Err(LoadError::UnsupportedFormat(dxgi_format)),
+        }
+    } else {
+        match four_cc_to_compressed(four_cc) {
+            Some(format) => format,
+            None => return Err(LoadError::UnsupportedFormat(four_cc)),
+        }
+    };
+
+    let level_count = if flags & DDSD_MIPMAPCOUNT != 0 && mip_map_count > 0 {mip_map_count} else {1};
+    if level_count > mip_levels(width, height) {
+        return Err(LoadError::UnsupportedLayout);
+    }
+
+    let mut levels = Vec::with_capacity(level_count);
+
+    for level in 0..level_count {
+        let size = compressed_size(format, level_size(width, level), level_size(height, level))?;
+
+        levels.push(read_slice(bytes, offset, size)?);
+        offset = checked_offset(offset, size)?;
+    }
+
+    Ok(CompressedImage::new(width, height, format, levels))
+}
+
+#[inline]
+fn four_cc_code(code: &[u8; 4]) -> u32 {
+    (code[0] as u32) | ((code[1] as u32) << 8) | ((code[2] as u32) << 16) | ((code[3] as u32) << 24)
+}
+
+#[inline]
+fn four_cc_to_compressed(four_cc: u32) -> Option<CompressedFormat> {
+    if four_cc == four_cc_code(b"DXT1") {
+        Some(CompressedFormat::RgbaS3tcDxt1)
+    } else if four_cc == four_cc_code(b"DXT3") {
+        Some(CompressedFormat::RgbaS3tcDxt3)
+    } else if four_cc == four_cc_code(b"DXT5") {
+        Some(CompressedFormat::RgbaS3tcDxt5)
+    } else if four_cc == four_cc_code(b"ATI1") || four_cc == four_cc_code(b"BC4U") {
+        Some(CompressedFormat::RedRgtc1)
+    } else if four_cc == four_cc_code(b"BC4S") {
+        Some(CompressedFormat::SignedRedRgtc1)
+    } else if four_cc == four_cc_code(b"ATI2") || four_cc == four_cc_code(b"BC5U") {
+        Some(CompressedFormat::RgRgtc2)
+    } else if four_cc == four_cc_code(b"BC5S") {
+        Some(CompressedFormat::SignedRgRgtc2)
+    } else {
+        None
+    }
+}
+
+#[inline]
+fn dxgi_format_to_compressed(dxgi_format: u32) -> Option<CompressedFormat> {
+    match dxgi_format {
+        71 => Some(CompressedFormat::RgbaS3tcDxt1),
+        74 => Some(CompressedFormat::RgbaS3tcDxt3),
+        77 => Some(CompressedFormat::RgbaS3tcDxt5),
+        80 => Some(CompressedFormat::RedRgtc1),
+        81 => Some(CompressedFormat::SignedRedRgtc1),
+        83 => Some(CompressedFormat::RgRgtc2),
+        84 => Some(CompressedFormat::SignedRgRgtc2),
+        95 => Some(CompressedFormat::RgbBptcUnsignedFloat),
+        96 => Some(CompressedFormat::RgbBptcSignedFloat),
+        98 => Some(CompressedFormat::RgbaBptcUnorm),
+        99 => Some(CompressedFormat::SrgbAlphaBptcUnorm),
+        _ => None,
+    }
+}
+
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use reader::write_u32;
+
+
+    fn dds_dxt5() -> [u8; 176] {
+        let mut bytes = [0u8; 176];
+        bytes[..4].copy_from_slice(&DDS_MAGIC);
+        write_u32(&mut bytes, 4, 124);
+        write_u32(&mut bytes, 8, DDSD_MIPMAPCOUNT);
+        write_u32(&mut bytes, 12, 4);
+        write_u32(&mut bytes, 16, 8);
+        write_u32(&mut bytes, 28, 2);
+        write_u32(&mut bytes, 76, 32);
+        write_u32(&mut bytes, 80, DDPF_FOURCC);
+        write_u32(&mut bytes, 84, four_cc_code(b"DXT5"));
+        bytes[128] = 0xAA;
+        bytes[160] = 0xBB;
+        bytes
+    }
+
+    fn dds_dx10_bc7() -> [u8; 164] {
+        let mut bytes = [0u8; 164];
+        bytes[..4].copy_from_slice(&DDS_MAGIC);
+        write_u32(&mut bytes, 4, 124);
+        write_u32(&mut bytes, 12, 4);
+        write_u32(&mut bytes, 16, 4);
+        write_u32(&mut bytes, 76, 32);
+        write_u32(&mut bytes, 80, DDPF_FOURCC);
+        write_u32(&mut bytes, 84, four_cc_code(b"DX10"));
+        write_u32(&mut bytes, 128, 98);
+        write_u32(&mut bytes, 132, DDS_DIMENSION_TEXTURE2D);
+        write_u32(&mut bytes, 140, 1);
+        bytes
+    }
+
+    #[test]
+    fn test_parse_dds() {
+        let bytes = dds_dxt5();
+        let image = parse(&bytes).unwrap();
+
+        assert_eq!(image.width(), 8);
+        assert_eq!(image.height(), 4);
+        assert_eq!(image.format(), CompressedFormat::RgbaS3tcDxt5);
+        assert_eq!(image.levels().len(), 2);
+        assert_eq!(image.levels()[0].len(), 32);
+        assert_eq!(image.levels()[0][0], 0xAA);
+        assert_eq!(image.levels()[1].len(), 16);
+        assert_eq!(image.levels()[1][0], 0xBB);
+    }
+
+    #[test]
+    fn test_parse_dds_dx10() {
+        let bytes = dds_dx10_bc7();
+        let image = parse(&bytes).unwrap();
+
+        assert_eq!(image.width(), 4);
+        assert_eq!(image.format(), CompressedFormat::RgbaBptcUnorm);
+        assert_eq!(image.levels().len(), 1);
+        assert_eq!(image.levels()[0].len(), 16);
+
+        let mut bytes = dds_dx10_bc7();
+        write_u32(&mut bytes, 136, DDS_RESOURCE_MISC_TEXTURECUBE);
+        assert_eq!(parse(&bytes).err(), Some(LoadError::UnsupportedLayout));
+
+        let mut bytes = dds_dx10_bc7();
+        write_u32(&mut bytes, 128, 28);
+        assert_eq!(parse(&bytes).err(), Some(LoadError::UnsupportedFormat(28)));
+    }
+
+    #[test]
+    fn test_parse_dds_errors() {
+        let bytes = dds_dxt5();
+        assert_eq!(parse(&bytes[..2]).err(), Some(LoadError::Truncated));
+        assert_eq!(parse(&bytes[..100]).err(), Some(LoadError::Truncated));
+        assert_eq!(parse(&bytes[..170]).err(), Some(LoadError::Truncated));
+
+        let mut bytes = dds_dxt5();
+        bytes[0] = 0;
+        assert_eq!(parse(&bytes).err(), Some(LoadError::InvalidIdentifier));
+
+        let mut bytes = dds_dxt5();
+        write_u32(&mut bytes, 80, 0x40);
+        assert_eq!(parse(&bytes).err(), Some(LoadError::UnsupportedFormat(0)));
+
+        let mut bytes = dds_dxt5();
+        write_u32(&mut bytes, 112, DDSCAPS2_CUBEMAP);
+        assert_eq!(parse(&bytes).err(), Some(LoadError::UnsupportedLayout));
+
+        let mut bytes = dds_dxt5();
+        write_u32(&mut bytes, 84, four_cc_code(b"ETC1"));
+        assert_eq!(parse(&bytes).err(), Some(LoadError::UnsupportedFormat(four_cc_code(b"ETC1"))));
+    }
+
+    #[test]
+    fn test_parse_dds_malformed() {
+        let mut bytes = dds_dxt5();
+        write_u32(&mut bytes, 28, 0xFFFFFFFF);
+        assert_eq!(parse(&bytes).err(), Some(LoadError::UnsupportedLayout));
+
+        let mut bytes = dds_dxt5();
+        write_u32(&mut bytes, 12, 0xFFFFFFFF);
+        write_u32(&mut bytes, 16, 0xFFFFFFFF);
+        assert_eq!(parse(&bytes).err(), Some(LoadError::Truncated));
+
+        let mut bytes = dds_dxt5();
+        write_u32(&mut bytes, 12, 0xFFFFFFFF);
+        write_u32(&mut bytes, 16, 0xFFFFFFFF);
+        write_u32(&mut bytes, 28, 32);
+        assert_eq!(parse(&bytes).err(), Some(LoadError::Truncated));
+    }
+}
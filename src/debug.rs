@@ -0,0 +1,122 @@
+use alloc::boxed::Box;
+
+use core::{mem, slice, str};
+
+use gl;
+use gl::types::*;
+
+use context::Context;
+use enums::{DebugSource, DebugType, DebugSeverity};
+
+
+pub type DebugCallback = Box<FnMut(&DebugMessage) + Send>;
+
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct DebugMessage<'a> {
+    source: DebugSource,
+    kind: DebugType,
+    id: GLuint,
+    severity: DebugSeverity,
+    message: &'a str,
+}
+
+impl<'a> DebugMessage<'a> {
+    #[inline(always)]
+    pub fn new(source: DebugSource, kind: DebugType, id: GLuint, severity: DebugSeverity, message: &'a str) -> Self {
+        DebugMessage {
+            source: source,
+            kind: kind,
+            id: id,
+            severity: severity,
+            message: message,
+        }
+    }
+
+    #[inline(always)]
+    pub fn source(&self) -> DebugSource { self.source }
+    #[inline(always)]
+    pub fn kind(&self) -> DebugType { self.kind }
+    #[inline(always)]
+    pub fn id(&self) -> GLuint { self.id }
+    #[inline(always)]
+    pub fn severity(&self) -> DebugSeverity { self.severity }
+    #[inline(always)]
+    pub fn message(&self) -> &'a str { self.message }
+}
+
+
+#[inline]
+pub fn object_label(context: &Context, identifier: GLenum, id: GLuint, label: &str) {
+    if id != 0 && context.has_debug_output() {
+        unsafe {
+            gl::ObjectLabel(identifier, id, label.len() as GLsizei, label.as_ptr() as *const GLchar);
+        }
+        gl_check!("object_label");
+    }
+}
+
+
+// Unwinding out of an extern "system" function into the driver is undefined
+// behaviour, so a panicking callback aborts instead. Without the std feature
+// there is no abort, this relies on a panic during unwinding aborting the
+// process.
+struct AbortOnUnwind;
+
+impl Drop for AbortOnUnwind {
+    #[cfg(feature = "std")]
+    #[inline]
+    fn drop(&mut self) {
+        ::std::process::abort();
+    }
+    #[cfg(not(feature = "std"))]
+    #[inline]
+    fn drop(&mut self) {
+        panic!("DebugCallback panicked, aborting instead of unwinding into the GL driver");
+    }
+}
+
+
+pub extern "system" fn debug_message_callback(
+    source: GLenum,
+    kind: GLenum,
+    id: GLuint,
+    severity: GLenum,
+    length: GLsizei,
+    message: *const GLchar,
+    user_param: *mut GLvoid,
+) {
+    if user_param.is_null() || message.is_null() {
+        return;
+    }
+
+    let length = if length < 0 {
+        let mut length = 0;
+        while unsafe { *message.offset(length as isize) } != 0 {
+            length += 1;
+        }
+        length
+    } else {
+        length as usize
+    };
+
+    let bytes = unsafe { slice::from_raw_parts(message as *const u8, length) };
+    let message = match str::from_utf8(bytes) {
+        Ok(message) => message,
+        Err(error) => match str::from_utf8(&bytes[..error.valid_up_to()]) {
+            Ok(message) => message,
+            Err(_) => "",
+        },
+    };
+
+    let callback = unsafe { &mut *(user_param as *mut DebugCallback) };
+    let guard = AbortOnUnwind;
+    callback(&DebugMessage::new(
+        DebugSource::from_gl(source),
+        DebugType::from_gl(kind),
+        id,
+        DebugSeverity::from_gl(severity),
+        message
+    ));
+    mem::forget(guard);
+}
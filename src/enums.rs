@@ -29,12 +29,94 @@ pub enum Depth {
     GreaterThanOrEqual,
     Always,
 }
+
+impl Depth {
+    #[inline]
+    pub fn to_gl(&self) -> GLenum {
+        match self {
+            &Depth::None => gl::ALWAYS,
+            &Depth::Never => gl::NEVER,
+            &Depth::LessThan => gl::LESS,
+            &Depth::Equal => gl::EQUAL,
+            &Depth::LessThanOrEqual => gl::LEQUAL,
+            &Depth::GreaterThan => gl::GREATER,
+            &Depth::NotEqual => gl::NOTEQUAL,
+            &Depth::GreaterThanOrEqual => gl::GEQUAL,
+            &Depth::Always => gl::ALWAYS,
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub enum StencilOp {
+    Keep,
+    Zero,
+    Replace,
+    Increment,
+    IncrementWrap,
+    Decrement,
+    DecrementWrap,
+    Invert,
+}
+
+impl StencilOp {
+    #[inline]
+    pub fn to_gl(&self) -> GLenum {
+        match self {
+            &StencilOp::Keep => gl::KEEP,
+            &StencilOp::Zero => gl::ZERO,
+            &StencilOp::Replace => gl::REPLACE,
+            &StencilOp::Increment => gl::INCR,
+            &StencilOp::IncrementWrap => gl::INCR_WRAP,
+            &StencilOp::Decrement => gl::DECR,
+            &StencilOp::DecrementWrap => gl::DECR_WRAP,
+            &StencilOp::Invert => gl::INVERT,
+        }
+    }
+}
+
 #[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
 pub enum FilterMode {
     None,
     Linear,
 }
 
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub enum TextureFilter {
+    Nearest,
+    Linear,
+}
+
+impl TextureFilter {
+    #[inline]
+    pub fn to_gl(&self) -> GLenum {
+        match self {
+            &TextureFilter::Nearest => gl::NEAREST,
+            &TextureFilter::Linear => gl::LINEAR,
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub enum MipmapFilter {
+    None,
+    Nearest,
+    Linear,
+}
+
+impl MipmapFilter {
+    #[inline]
+    pub fn to_gl(&self, min_filter: TextureFilter) -> GLenum {
+        match (self, min_filter) {
+            (&MipmapFilter::None, min_filter) => min_filter.to_gl(),
+            (&MipmapFilter::Nearest, TextureFilter::Nearest) => gl::NEAREST_MIPMAP_NEAREST,
+            (&MipmapFilter::Nearest, TextureFilter::Linear) => gl::LINEAR_MIPMAP_NEAREST,
+            (&MipmapFilter::Linear, TextureFilter::Nearest) => gl::NEAREST_MIPMAP_LINEAR,
+            (&MipmapFilter::Linear, TextureFilter::Linear) => gl::LINEAR_MIPMAP_LINEAR,
+        }
+    }
+}
+
 #[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
 pub enum DrawMode {
     Points,
@@ -157,21 +239,99 @@ impl BufferTarget {
 
 #[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
 pub enum Attachment {
-    Color,
+    Color(usize),
     Depth,
-    Stencil
+    Stencil,
+    DepthStencil,
 }
 
 impl Attachment {
     #[inline]
     pub fn to_gl(&self) -> GLenum {
         match self {
-            &Attachment::Color => gl::COLOR_ATTACHMENT0,
+            &Attachment::Color(index) => gl::COLOR_ATTACHMENT0 + index as GLenum,
             &Attachment::Depth => gl::DEPTH_ATTACHMENT,
             &Attachment::Stencil => gl::STENCIL_ATTACHMENT,
+            &Attachment::DepthStencil => gl::DEPTH_STENCIL_ATTACHMENT,
         }
     }
 }
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub enum LoadAction {
+    Clear,
+    Load,
+    DontCare,
+}
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub enum StoreAction {
+    Store,
+    Discard,
+    Resolve,
+}
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub enum FramebufferStatus {
+    Complete,
+    Undefined,
+    IncompleteAttachment,
+    IncompleteMissingAttachment,
+    IncompleteDrawBuffer,
+    IncompleteReadBuffer,
+    Unsupported,
+    IncompleteMultisample,
+    IncompleteLayerTargets,
+    Unknown(GLenum),
+}
+
+impl FramebufferStatus {
+    #[inline]
+    pub fn to_gl(&self) -> GLenum {
+        match self {
+            &FramebufferStatus::Complete => gl::FRAMEBUFFER_COMPLETE,
+            &FramebufferStatus::Undefined => gl::FRAMEBUFFER_UNDEFINED,
+            &FramebufferStatus::IncompleteAttachment => gl::FRAMEBUFFER_INCOMPLETE_ATTACHMENT,
+            &FramebufferStatus::IncompleteMissingAttachment => gl::FRAMEBUFFER_INCOMPLETE_MISSING_ATTACHMENT,
+            &FramebufferStatus::IncompleteDrawBuffer => gl::FRAMEBUFFER_INCOMPLETE_DRAW_BUFFER,
+            &FramebufferStatus::IncompleteReadBuffer => gl::FRAMEBUFFER_INCOMPLETE_READ_BUFFER,
+            &FramebufferStatus::Unsupported => gl::FRAMEBUFFER_UNSUPPORTED,
+            &FramebufferStatus::IncompleteMultisample => gl::FRAMEBUFFER_INCOMPLETE_MULTISAMPLE,
+            &FramebufferStatus::IncompleteLayerTargets => gl::FRAMEBUFFER_INCOMPLETE_LAYER_TARGETS,
+            &FramebufferStatus::Unknown(status) => status,
+        }
+    }
+
+    #[inline]
+    pub fn from_gl(status: GLenum) -> Self {
+        match status {
+            gl::FRAMEBUFFER_COMPLETE => FramebufferStatus::Complete,
+            gl::FRAMEBUFFER_UNDEFINED => FramebufferStatus::Undefined,
+            gl::FRAMEBUFFER_INCOMPLETE_ATTACHMENT => FramebufferStatus::IncompleteAttachment,
+            gl::FRAMEBUFFER_INCOMPLETE_MISSING_ATTACHMENT => FramebufferStatus::IncompleteMissingAttachment,
+            gl::FRAMEBUFFER_INCOMPLETE_DRAW_BUFFER => FramebufferStatus::IncompleteDrawBuffer,
+            gl::FRAMEBUFFER_INCOMPLETE_READ_BUFFER => FramebufferStatus::IncompleteReadBuffer,
+            gl::FRAMEBUFFER_UNSUPPORTED => FramebufferStatus::Unsupported,
+            gl::FRAMEBUFFER_INCOMPLETE_MULTISAMPLE => FramebufferStatus::IncompleteMultisample,
+            gl::FRAMEBUFFER_INCOMPLETE_LAYER_TARGETS => FramebufferStatus::IncompleteLayerTargets,
+            status => FramebufferStatus::Unknown(status),
+        }
+    }
+
+    #[inline]
+    pub fn description(&self) -> &'static str {
+        match self {
+            &FramebufferStatus::Complete => "framebuffer is complete",
+            &FramebufferStatus::Undefined => "the default framebuffer does not exist",
+            &FramebufferStatus::IncompleteAttachment => "an attachment is not renderable or has a zero size",
+            &FramebufferStatus::IncompleteMissingAttachment => "framebuffer has no attachments",
+            &FramebufferStatus::IncompleteDrawBuffer => "a draw buffer names an attachment with no image",
+            &FramebufferStatus::IncompleteReadBuffer => "the read buffer names an attachment with no image",
+            &FramebufferStatus::Unsupported => "the combination of attachment formats is not supported",
+            &FramebufferStatus::IncompleteMultisample => "attachments have different sample counts",
+            &FramebufferStatus::IncompleteLayerTargets => "attachments mix layered and non-layered targets",
+            &FramebufferStatus::Unknown(_) => "unknown framebuffer status",
+        }
+    }
+}
+
 #[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
 pub enum TextureFormat {
     Red, RG, RGB, BGR, RGBA, BGRA,
@@ -202,6 +362,382 @@ impl TextureFormat {
             &TextureFormat::DepthStencil => gl::DEPTH_STENCIL,
         }
     }
+
+    #[inline]
+    pub fn is_integer(&self) -> bool {
+        match self {
+            &TextureFormat::RedInteger |
+            &TextureFormat::RGInteger |
+            &TextureFormat::RGBInteger |
+            &TextureFormat::BGRInteger |
+            &TextureFormat::RGBAInteger |
+            &TextureFormat::BGRAInteger |
+            &TextureFormat::StencilInteger => true,
+            _ => false,
+        }
+    }
+
+    #[inline]
+    pub fn components(&self) -> usize {
+        match self {
+            &TextureFormat::Red |
+            &TextureFormat::RedInteger |
+            &TextureFormat::StencilInteger |
+            &TextureFormat::DepthComponent => 1,
+            &TextureFormat::RG |
+            &TextureFormat::RGInteger |
+            &TextureFormat::DepthStencil => 2,
+            &TextureFormat::RGB |
+            &TextureFormat::BGR |
+            &TextureFormat::RGBInteger |
+            &TextureFormat::BGRInteger => 3,
+            &TextureFormat::RGBA |
+            &TextureFormat::BGRA |
+            &TextureFormat::RGBAInteger |
+            &TextureFormat::BGRAInteger => 4,
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub enum InternalFormat {
+    Red, RG, RGB, RGBA,
+    DepthComponent, DepthStencil,
+
+    R8, R8SNorm, R16F, R32F, R8UI, R8I, R16UI, R16I, R32UI, R32I,
+    RG8, RG8SNorm, RG16F, RG32F, RG8UI, RG8I, RG16UI, RG16I, RG32UI, RG32I,
+    RGB8, SRGB8, RGB565, RGB8SNorm, R11FG11FB10F, RGB9E5, RGB16F, RGB32F,
+    RGB8UI, RGB8I, RGB16UI, RGB16I, RGB32UI, RGB32I,
+    RGBA8, SRGB8Alpha8, RGBA8SNorm, RGB5A1, RGBA4, RGB10A2, RGBA16F, RGBA32F,
+    RGBA8UI, RGBA8I, RGB10A2UI, RGBA16UI, RGBA16I, RGBA32UI, RGBA32I,
+
+    DepthComponent16, DepthComponent24, DepthComponent32F,
+    Depth24Stencil8, Depth32FStencil8,
+    StencilIndex8,
+}
+
+impl InternalFormat {
+    #[inline]
+    pub fn to_gl(&self) -> GLenum {
+        match self {
+            &InternalFormat::Red => gl::RED,
+            &InternalFormat::RG => gl::RG,
+            &InternalFormat::RGB => gl::RGB,
+            &InternalFormat::RGBA => gl::RGBA,
+            &InternalFormat::DepthComponent => gl::DEPTH_COMPONENT,
+            &InternalFormat::DepthStencil => gl::DEPTH_STENCIL,
+            &InternalFormat::R8 => gl::R8,
+            &InternalFormat::R8SNorm => gl::R8_SNORM,
+            &InternalFormat::R16F => gl::R16F,
+            &InternalFormat::R32F => gl::R32F,
+            &InternalFormat::R8UI => gl::R8UI,
+            &InternalFormat::R8I => gl::R8I,
+            &InternalFormat::R16UI => gl::R16UI,
+            &InternalFormat::R16I => gl::R16I,
+            &InternalFormat::R32UI => gl::R32UI,
+            &InternalFormat::R32I => gl::R32I,
+            &InternalFormat::RG8 => gl::RG8,
+            &InternalFormat::RG8SNorm => gl::RG8_SNORM,
+            &InternalFormat::RG16F => gl::RG16F,
+            &InternalFormat::RG32F => gl::RG32F,
+            &InternalFormat::RG8UI => gl::RG8UI,
+            &InternalFormat::RG8I => gl::RG8I,
+            &InternalFormat::RG16UI => gl::RG16UI,
+            &InternalFormat::RG16I => gl::RG16I,
+            &InternalFormat::RG32UI => gl::RG32UI,
+            &InternalFormat::RG32I => gl::RG32I,
+            &InternalFormat::RGB8 => gl::RGB8,
+            &InternalFormat::SRGB8 => gl::SRGB8,
+            &InternalFormat::RGB565 => gl::RGB565,
+            &InternalFormat::RGB8SNorm => gl::RGB8_SNORM,
+            &InternalFormat::R11FG11FB10F => gl::R11F_G11F_B10F,
+            &InternalFormat::RGB9E5 => gl::RGB9_E5,
+            &InternalFormat::RGB16F => gl::RGB16F,
+            &InternalFormat::RGB32F => gl::RGB32F,
+            &InternalFormat::RGB8UI => gl::RGB8UI,
+            &InternalFormat::RGB8I => gl::RGB8I,
+            &InternalFormat::RGB16UI => gl::RGB16UI,
+            &InternalFormat::RGB16I => gl::RGB16I,
+            &InternalFormat::RGB32UI => gl::RGB32UI,
+            &InternalFormat::RGB32I => gl::RGB32I,
+            &InternalFormat::RGBA8 => gl::RGBA8,
+            &InternalFormat::SRGB8Alpha8 => gl::SRGB8_ALPHA8,
+            &InternalFormat::RGBA8SNorm => gl::RGBA8_SNORM,
+            &InternalFormat::RGB5A1 => gl::RGB5_A1,
+            &InternalFormat::RGBA4 => gl::RGBA4,
+            &InternalFormat::RGB10A2 => gl::RGB10_A2,
+            &InternalFormat::RGBA16F => gl::RGBA16F,
+            &InternalFormat::RGBA32F => gl::RGBA32F,
+            &InternalFormat::RGBA8UI => gl::RGBA8UI,
+            &InternalFormat::RGBA8I => gl::RGBA8I,
+            &InternalFormat::RGB10A2UI => gl::RGB10_A2UI,
+            &InternalFormat::RGBA16UI => gl::RGBA16UI,
+            &InternalFormat::RGBA16I => gl::RGBA16I,
+            &InternalFormat::RGBA32UI => gl::RGBA32UI,
+            &InternalFormat::RGBA32I => gl::RGBA32I,
+            &InternalFormat::DepthComponent16 => gl::DEPTH_COMPONENT16,
+            &InternalFormat::DepthComponent24 => gl::DEPTH_COMPONENT24,
+            &InternalFormat::DepthComponent32F => gl::DEPTH_COMPONENT32F,
+            &InternalFormat::Depth24Stencil8 => gl::DEPTH24_STENCIL8,
+            &InternalFormat::Depth32FStencil8 => gl::DEPTH32F_STENCIL8,
+            &InternalFormat::StencilIndex8 => gl::STENCIL_INDEX8,
+        }
+    }
+
+    #[inline]
+    pub fn from_format(format: TextureFormat, kind: TextureKind) -> Self {
+        match (format, kind) {
+            (TextureFormat::Red, _) => InternalFormat::Red,
+            (TextureFormat::RG, _) => InternalFormat::RG,
+            (TextureFormat::RGB, _) | (TextureFormat::BGR, _) => InternalFormat::RGB,
+            (TextureFormat::RGBA, _) | (TextureFormat::BGRA, _) => InternalFormat::RGBA,
+
+            (TextureFormat::RedInteger, TextureKind::UnsignedByte) => InternalFormat::R8UI,
+            (TextureFormat::RedInteger, TextureKind::Byte) => InternalFormat::R8I,
+            (TextureFormat::RedInteger, TextureKind::UnsignedShort) => InternalFormat::R16UI,
+            (TextureFormat::RedInteger, TextureKind::Short) => InternalFormat::R16I,
+            (TextureFormat::RedInteger, TextureKind::Int) => InternalFormat::R32I,
+            (TextureFormat::RedInteger, _) => InternalFormat::R32UI,
+
+            (TextureFormat::RGInteger, TextureKind::UnsignedByte) => InternalFormat::RG8UI,
+            (TextureFormat::RGInteger, TextureKind::Byte) => InternalFormat::RG8I,
+            (TextureFormat::RGInteger, TextureKind::UnsignedShort) => InternalFormat::RG16UI,
+            (TextureFormat::RGInteger, TextureKind::Short) => InternalFormat::RG16I,
+            (TextureFormat::RGInteger, TextureKind::Int) => InternalFormat::RG32I,
+            (TextureFormat::RGInteger, _) => InternalFormat::RG32UI,
+
+            (TextureFormat::RGBInteger, TextureKind::UnsignedByte) |
+            (TextureFormat::BGRInteger, TextureKind::UnsignedByte) => InternalFormat::RGB8UI,
+            (TextureFormat::RGBInteger, TextureKind::Byte) |
+            (TextureFormat::BGRInteger, TextureKind::Byte) => InternalFormat::RGB8I,
+            (TextureFormat::RGBInteger, TextureKind::UnsignedShort) |
+            (TextureFormat::BGRInteger, TextureKind::UnsignedShort) => InternalFormat::RGB16UI,
+            (TextureFormat::RGBInteger, TextureKind::Short) |
+            (TextureFormat::BGRInteger, TextureKind::Short) => InternalFormat::RGB16I,
+            (TextureFormat::RGBInteger, TextureKind::Int) |
+            (TextureFormat::BGRInteger, TextureKind::Int) => InternalFormat::RGB32I,
+            (TextureFormat::RGBInteger, _) | (TextureFormat::BGRInteger, _) => InternalFormat::RGB32UI,
+
+            (TextureFormat::RGBAInteger, TextureKind::UnsignedByte) |
+            (TextureFormat::BGRAInteger, TextureKind::UnsignedByte) => InternalFormat::RGBA8UI,
+            (TextureFormat::RGBAInteger, TextureKind::Byte) |
+            (TextureFormat::BGRAInteger, TextureKind::Byte) => InternalFormat::RGBA8I,
+            (TextureFormat::RGBAInteger, TextureKind::UnsignedShort) |
+            (TextureFormat::BGRAInteger, TextureKind::UnsignedShort) => InternalFormat::RGBA16UI,
+            (TextureFormat::RGBAInteger, TextureKind::Short) |
+            (TextureFormat::BGRAInteger, TextureKind::Short) => InternalFormat::RGBA16I,
+            (TextureFormat::RGBAInteger, TextureKind::Int) |
+            (TextureFormat::BGRAInteger, TextureKind::Int) => InternalFormat::RGBA32I,
+            (TextureFormat::RGBAInteger, TextureKind::UnsignedInt2101010Rev) |
+            (TextureFormat::BGRAInteger, TextureKind::UnsignedInt2101010Rev) => InternalFormat::RGB10A2UI,
+            (TextureFormat::RGBAInteger, _) | (TextureFormat::BGRAInteger, _) => InternalFormat::RGBA32UI,
+
+            (TextureFormat::StencilInteger, _) => InternalFormat::StencilIndex8,
+            (TextureFormat::DepthComponent, _) => InternalFormat::DepthComponent,
+            (TextureFormat::DepthStencil, _) => InternalFormat::DepthStencil,
+        }
+    }
+
+    #[inline]
+    pub fn format(&self) -> TextureFormat {
+        match self {
+            &InternalFormat::Red => TextureFormat::Red,
+            &InternalFormat::RG => TextureFormat::RG,
+            &InternalFormat::RGB => TextureFormat::RGB,
+            &InternalFormat::RGBA => TextureFormat::RGBA,
+            &InternalFormat::DepthComponent => TextureFormat::DepthComponent,
+            &InternalFormat::DepthStencil => TextureFormat::DepthStencil,
+            &InternalFormat::R8 => TextureFormat::Red,
+            &InternalFormat::R8SNorm => TextureFormat::Red,
+            &InternalFormat::R16F => TextureFormat::Red,
+            &InternalFormat::R32F => TextureFormat::Red,
+            &InternalFormat::R8UI => TextureFormat::RedInteger,
+            &InternalFormat::R8I => TextureFormat::RedInteger,
+            &InternalFormat::R16UI => TextureFormat::RedInteger,
+            &InternalFormat::R16I => TextureFormat::RedInteger,
+            &InternalFormat::R32UI => TextureFormat::RedInteger,
+            &InternalFormat::R32I => TextureFormat::RedInteger,
+            &InternalFormat::RG8 => TextureFormat::RG,
+            &InternalFormat::RG8SNorm => TextureFormat::RG,
+            &InternalFormat::RG16F => TextureFormat::RG,
+            &InternalFormat::RG32F => TextureFormat::RG,
+            &InternalFormat::RG8UI => TextureFormat::RGInteger,
+            &InternalFormat::RG8I => TextureFormat::RGInteger,
+            &InternalFormat::RG16UI => TextureFormat::RGInteger,
+            &InternalFormat::RG16I => TextureFormat::RGInteger,
+            &InternalFormat::RG32UI => TextureFormat::RGInteger,
+            &InternalFormat::RG32I => TextureFormat::RGInteger,
+            &InternalFormat::RGB8 => TextureFormat::RGB,
+            &InternalFormat::SRGB8 => TextureFormat::RGB,
+            &InternalFormat::RGB565 => TextureFormat::RGB,
+            &InternalFormat::RGB8SNorm => TextureFormat::RGB,
+            &InternalFormat::R11FG11FB10F => TextureFormat::RGB,
+            &InternalFormat::RGB9E5 => TextureFormat::RGB,
+            &InternalFormat::RGB16F => TextureFormat::RGB,
+            &InternalFormat::RGB32F => TextureFormat::RGB,
+            &InternalFormat::RGB8UI => TextureFormat::RGBInteger,
+            &InternalFormat::RGB8I => TextureFormat::RGBInteger,
+            &InternalFormat::RGB16UI => TextureFormat::RGBInteger,
+            &InternalFormat::RGB16I => TextureFormat::RGBInteger,
+            &InternalFormat::RGB32UI => TextureFormat::RGBInteger,
+            &InternalFormat::RGB32I => TextureFormat::RGBInteger,
+            &InternalFormat::RGBA8 => TextureFormat::RGBA,
+            &InternalFormat::SRGB8Alpha8 => TextureFormat::RGBA,
+            &InternalFormat::RGBA8SNorm => TextureFormat::RGBA,
+            &InternalFormat::RGB5A1 => TextureFormat::RGBA,
+            &InternalFormat::RGBA4 => TextureFormat::RGBA,
+            &InternalFormat::RGB10A2 => TextureFormat::RGBA,
+            &InternalFormat::RGBA16F => TextureFormat::RGBA,
+            &InternalFormat::RGBA32F => TextureFormat::RGBA,
+            &InternalFormat::RGBA8UI => TextureFormat::RGBAInteger,
+            &InternalFormat::RGBA8I => TextureFormat::RGBAInteger,
+            &InternalFormat::RGB10A2UI => TextureFormat::RGBAInteger,
+            &InternalFormat::RGBA16UI => TextureFormat::RGBAInteger,
+            &InternalFormat::RGBA16I => TextureFormat::RGBAInteger,
+            &InternalFormat::RGBA32UI => TextureFormat::RGBAInteger,
+            &InternalFormat::RGBA32I => TextureFormat::RGBAInteger,
+            &InternalFormat::DepthComponent16 => TextureFormat::DepthComponent,
+            &InternalFormat::DepthComponent24 => TextureFormat::DepthComponent,
+            &InternalFormat::DepthComponent32F => TextureFormat::DepthComponent,
+            &InternalFormat::Depth24Stencil8 => TextureFormat::DepthStencil,
+            &InternalFormat::Depth32FStencil8 => TextureFormat::DepthStencil,
+            &InternalFormat::StencilIndex8 => TextureFormat::StencilInteger,
+        }
+    }
+
+    #[inline]
+    pub fn kinds(&self) -> &'static [TextureKind] {
+        static UNSIGNED_BYTE: [TextureKind; 1] = [TextureKind::UnsignedByte];
+        static RGB_KINDS: [TextureKind; 2] = [TextureKind::UnsignedByte, TextureKind::UnsignedShort565];
+        static RGBA_KINDS: [TextureKind; 3] = [TextureKind::UnsignedByte, TextureKind::UnsignedShort4444, TextureKind::UnsignedShort5551];
+        static DEPTH_KINDS: [TextureKind; 2] = [TextureKind::UnsignedShort, TextureKind::UnsignedInt];
+        static UNSIGNED_INT_24_8: [TextureKind; 1] = [TextureKind::UnsignedInt248];
+        static BYTE: [TextureKind; 1] = [TextureKind::Byte];
+        static HALF_FLOAT_OR_FLOAT: [TextureKind; 2] = [TextureKind::HalfFloat, TextureKind::Float];
+        static FLOAT: [TextureKind; 1] = [TextureKind::Float];
+        static UNSIGNED_SHORT: [TextureKind; 1] = [TextureKind::UnsignedShort];
+        static SHORT: [TextureKind; 1] = [TextureKind::Short];
+        static UNSIGNED_INT: [TextureKind; 1] = [TextureKind::UnsignedInt];
+        static INT: [TextureKind; 1] = [TextureKind::Int];
+        static R11F_G11F_B10F_KINDS: [TextureKind; 3] = [TextureKind::UnsignedInt10F11F11FRev, TextureKind::HalfFloat, TextureKind::Float];
+        static RGB9_E5_KINDS: [TextureKind; 3] = [TextureKind::UnsignedInt5999Rev, TextureKind::HalfFloat, TextureKind::Float];
+        static RGB5_A1_KINDS: [TextureKind; 3] = [TextureKind::UnsignedByte, TextureKind::UnsignedShort5551, TextureKind::UnsignedInt2101010Rev];
+        static RGBA4_KINDS: [TextureKind; 2] = [TextureKind::UnsignedByte, TextureKind::UnsignedShort4444];
+        static UNSIGNED_INT_2_10_10_10_REV: [TextureKind; 1] = [TextureKind::UnsignedInt2101010Rev];
+        static FLOAT_32_UNSIGNED_INT_24_8_REV: [TextureKind; 1] = [TextureKind::Float32UnsignedInt248Rev];
+
+        match self {
+            &InternalFormat::Red |
+            &InternalFormat::RG |
+            &InternalFormat::R8 |
+            &InternalFormat::R8UI |
+            &InternalFormat::RG8 |
+            &InternalFormat::RG8UI |
+            &InternalFormat::RGB8 |
+            &InternalFormat::SRGB8 |
+            &InternalFormat::RGB8UI |
+            &InternalFormat::RGBA8 |
+            &InternalFormat::SRGB8Alpha8 |
+            &InternalFormat::RGBA8UI |
+            &InternalFormat::StencilIndex8 => &UNSIGNED_BYTE,
+            &InternalFormat::RGB |
+            &InternalFormat::RGB565 => &RGB_KINDS,
+            &InternalFormat::RGBA => &RGBA_KINDS,
+            &InternalFormat::DepthComponent |
+            &InternalFormat::DepthComponent16 => &DEPTH_KINDS,
+            &InternalFormat::DepthStencil |
+            &InternalFormat::Depth24Stencil8 => &UNSIGNED_INT_24_8,
+            &InternalFormat::R8SNorm |
+            &InternalFormat::R8I |
+            &InternalFormat::RG8SNorm |
+            &InternalFormat::RG8I |
+            &InternalFormat::RGB8SNorm |
+            &InternalFormat::RGB8I |
+            &InternalFormat::RGBA8SNorm |
+            &InternalFormat::RGBA8I => &BYTE,
+            &InternalFormat::R16F |
+            &InternalFormat::RG16F |
+            &InternalFormat::RGB16F |
+            &InternalFormat::RGBA16F => &HALF_FLOAT_OR_FLOAT,
+            &InternalFormat::R32F |
+            &InternalFormat::RG32F |
+            &InternalFormat::RGB32F |
+            &InternalFormat::RGBA32F |
+            &InternalFormat::DepthComponent32F => &FLOAT,
+            &InternalFormat::R16UI |
+            &InternalFormat::RG16UI |
+            &InternalFormat::RGB16UI |
+            &InternalFormat::RGBA16UI => &UNSIGNED_SHORT,
+            &InternalFormat::R16I |
+            &InternalFormat::RG16I |
+            &InternalFormat::RGB16I |
+            &InternalFormat::RGBA16I => &SHORT,
+            &InternalFormat::R32UI |
+            &InternalFormat::RG32UI |
+            &InternalFormat::RGB32UI |
+            &InternalFormat::RGBA32UI |
+            &InternalFormat::DepthComponent24 => &UNSIGNED_INT,
+            &InternalFormat::R32I |
+            &InternalFormat::RG32I |
+            &InternalFormat::RGB32I |
+            &InternalFormat::RGBA32I => &INT,
+            &InternalFormat::R11FG11FB10F => &R11F_G11F_B10F_KINDS,
+            &InternalFormat::RGB9E5 => &RGB9_E5_KINDS,
+            &InternalFormat::RGB5A1 => &RGB5_A1_KINDS,
+            &InternalFormat::RGBA4 => &RGBA4_KINDS,
+            &InternalFormat::RGB10A2 |
+            &InternalFormat::RGB10A2UI => &UNSIGNED_INT_2_10_10_10_REV,
+            &InternalFormat::Depth32FStencil8 => &FLOAT_32_UNSIGNED_INT_24_8_REV,
+        }
+    }
+
+    #[inline]
+    pub fn is_sized(&self) -> bool {
+        match self {
+            &InternalFormat::Red |
+            &InternalFormat::RG |
+            &InternalFormat::RGB |
+            &InternalFormat::RGBA |
+            &InternalFormat::DepthComponent |
+            &InternalFormat::DepthStencil => false,
+            _ => true,
+        }
+    }
+
+    #[inline]
+    pub fn requires_gl3(&self) -> bool {
+        match self {
+            &InternalFormat::RGB |
+            &InternalFormat::RGBA |
+            &InternalFormat::DepthComponent |
+            &InternalFormat::RGB8 |
+            &InternalFormat::RGB565 |
+            &InternalFormat::RGBA8 |
+            &InternalFormat::RGB5A1 |
+            &InternalFormat::RGBA4 |
+            &InternalFormat::DepthComponent16 => false,
+            _ => true,
+        }
+    }
+
+    #[inline]
+    pub fn is_integer(&self) -> bool {
+        self.format().is_integer()
+    }
+    #[inline]
+    pub fn is_depth(&self) -> bool {
+        match self.format() {
+            TextureFormat::DepthComponent | TextureFormat::DepthStencil => true,
+            _ => false,
+        }
+    }
+    #[inline]
+    pub fn is_stencil(&self) -> bool {
+        match self.format() {
+            TextureFormat::StencilInteger | TextureFormat::DepthStencil => true,
+            _ => false,
+        }
+    }
 }
 
 #[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
@@ -231,6 +767,12 @@ pub enum TextureKind {
 
     UnsignedInt1010102,
     UnsignedInt2101010Rev,
+
+    HalfFloat,
+    UnsignedInt248,
+    UnsignedInt10F11F11FRev,
+    UnsignedInt5999Rev,
+    Float32UnsignedInt248Rev,
 }
 
 impl TextureKind {
@@ -262,6 +804,89 @@ impl TextureKind {
 
             &TextureKind::UnsignedInt1010102 => gl::UNSIGNED_INT_10_10_10_2,
             &TextureKind::UnsignedInt2101010Rev => gl::UNSIGNED_INT_2_10_10_10_REV,
+
+            &TextureKind::HalfFloat => gl::HALF_FLOAT,
+            &TextureKind::UnsignedInt248 => gl::UNSIGNED_INT_24_8,
+            &TextureKind::UnsignedInt10F11F11FRev => gl::UNSIGNED_INT_10F_11F_11F_REV,
+            &TextureKind::UnsignedInt5999Rev => gl::UNSIGNED_INT_5_9_9_9_REV,
+            &TextureKind::Float32UnsignedInt248Rev => gl::FLOAT_32_UNSIGNED_INT_24_8_REV,
+        }
+    }
+
+    #[inline]
+    pub fn size(&self) -> usize {
+        match self {
+            &TextureKind::UnsignedByte |
+            &TextureKind::Byte |
+            &TextureKind::UnsignedByte332 |
+            &TextureKind::UnsignedByte223Rev => 1,
+
+            &TextureKind::UnsignedShort |
+            &TextureKind::Short |
+            &TextureKind::HalfFloat |
+            &TextureKind::UnsignedShort565 |
+            &TextureKind::UnsignedShort565Rev |
+            &TextureKind::UnsignedShort4444 |
+            &TextureKind::UnsignedShort4444Rev |
+            &TextureKind::UnsignedShort5551 |
+            &TextureKind::UnsignedShort1555Rev => 2,
+
+            &TextureKind::Float32UnsignedInt248Rev => 8,
+
+            _ => 4,
+        }
+    }
+
+    #[inline]
+    pub fn pixel_size(&self, format: TextureFormat) -> usize {
+        if self.packed_formats().is_some() {
+            self.size()
+        } else {
+            self.size() * format.components()
+        }
+    }
+
+    #[inline]
+    pub fn is_float(&self) -> bool {
+        match self {
+            &TextureKind::Float | &TextureKind::HalfFloat => true,
+            _ => false,
+        }
+    }
+
+    #[inline]
+    pub fn packed_formats(&self) -> Option<&'static [TextureFormat]> {
+        static RGB: [TextureFormat; 4] = [
+            TextureFormat::RGB, TextureFormat::BGR, TextureFormat::RGBInteger, TextureFormat::BGRInteger,
+        ];
+        static RGBA: [TextureFormat; 4] = [
+            TextureFormat::RGBA, TextureFormat::BGRA, TextureFormat::RGBAInteger, TextureFormat::BGRAInteger,
+        ];
+        static RGB_FLOAT: [TextureFormat; 1] = [TextureFormat::RGB];
+        static DEPTH_STENCIL: [TextureFormat; 1] = [TextureFormat::DepthStencil];
+
+        match self {
+            &TextureKind::UnsignedByte332 |
+            &TextureKind::UnsignedByte223Rev |
+            &TextureKind::UnsignedShort565 |
+            &TextureKind::UnsignedShort565Rev => Some(&RGB),
+
+            &TextureKind::UnsignedShort4444 |
+            &TextureKind::UnsignedShort4444Rev |
+            &TextureKind::UnsignedShort5551 |
+            &TextureKind::UnsignedShort1555Rev |
+            &TextureKind::UnsignedInt8888 |
+            &TextureKind::UnsignedInt8888Rev |
+            &TextureKind::UnsignedInt1010102 |
+            &TextureKind::UnsignedInt2101010Rev => Some(&RGBA),
+
+            &TextureKind::UnsignedInt10F11F11FRev |
+            &TextureKind::UnsignedInt5999Rev => Some(&RGB_FLOAT),
+
+            &TextureKind::UnsignedInt248 |
+            &TextureKind::Float32UnsignedInt248Rev => Some(&DEPTH_STENCIL),
+
+            _ => None,
         }
     }
 }
@@ -270,6 +895,7 @@ impl TextureKind {
 pub enum TextureWrap {
     Repeat,
     Clamp,
+    ClampToBorder,
     MirroredRepeat,
 }
 
@@ -279,8 +905,427 @@ impl TextureWrap {
         match self {
             &TextureWrap::Repeat => gl::REPEAT,
             &TextureWrap::Clamp => gl::CLAMP_TO_EDGE,
+            &TextureWrap::ClampToBorder => gl::CLAMP_TO_BORDER,
             &TextureWrap::MirroredRepeat => gl::MIRRORED_REPEAT,
         }
     }
 
 }
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub enum CubeFace {
+    PositiveX,
+    NegativeX,
+    PositiveY,
+    NegativeY,
+    PositiveZ,
+    NegativeZ,
+}
+
+impl CubeFace {
+    #[inline]
+    pub fn to_gl(&self) -> GLenum {
+        match self {
+            &CubeFace::PositiveX => gl::TEXTURE_CUBE_MAP_POSITIVE_X,
+            &CubeFace::NegativeX => gl::TEXTURE_CUBE_MAP_NEGATIVE_X,
+            &CubeFace::PositiveY => gl::TEXTURE_CUBE_MAP_POSITIVE_Y,
+            &CubeFace::NegativeY => gl::TEXTURE_CUBE_MAP_NEGATIVE_Y,
+            &CubeFace::PositiveZ => gl::TEXTURE_CUBE_MAP_POSITIVE_Z,
+            &CubeFace::NegativeZ => gl::TEXTURE_CUBE_MAP_NEGATIVE_Z,
+        }
+    }
+}
+
+const COMPRESSED_RGB_S3TC_DXT1: GLenum = 0x83F0;
+const COMPRESSED_RGBA_S3TC_DXT1: GLenum = 0x83F1;
+const COMPRESSED_RGBA_S3TC_DXT3: GLenum = 0x83F2;
+const COMPRESSED_RGBA_S3TC_DXT5: GLenum = 0x83F3;
+
+const COMPRESSED_RGBA_ASTC_4X4: GLenum = 0x93B0;
+const COMPRESSED_RGBA_ASTC_5X5: GLenum = 0x93B2;
+const COMPRESSED_RGBA_ASTC_6X6: GLenum = 0x93B4;
+const COMPRESSED_RGBA_ASTC_8X8: GLenum = 0x93B7;
+const COMPRESSED_RGBA_ASTC_10X10: GLenum = 0x93BB;
+const COMPRESSED_RGBA_ASTC_12X12: GLenum = 0x93BD;
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub enum CompressedFormat {
+    RgbS3tcDxt1,
+    RgbaS3tcDxt1,
+    RgbaS3tcDxt3,
+    RgbaS3tcDxt5,
+
+    RedRgtc1,
+    SignedRedRgtc1,
+    RgRgtc2,
+    SignedRgRgtc2,
+
+    RgbBptcSignedFloat,
+    RgbBptcUnsignedFloat,
+    RgbaBptcUnorm,
+    SrgbAlphaBptcUnorm,
+
+    Rgb8Etc2,
+    Srgb8Etc2,
+    Rgb8PunchthroughAlpha1Etc2,
+    Rgba8Etc2Eac,
+    Srgb8Alpha8Etc2Eac,
+    R11Eac,
+    SignedR11Eac,
+    Rg11Eac,
+    SignedRg11Eac,
+
+    RgbaAstc4x4,
+    RgbaAstc5x5,
+    RgbaAstc6x6,
+    RgbaAstc8x8,
+    RgbaAstc10x10,
+    RgbaAstc12x12,
+}
+
+impl CompressedFormat {
+    #[inline]
+    pub fn to_gl(&self) -> GLenum {
+        match self {
+            &CompressedFormat::RgbS3tcDxt1 => COMPRESSED_RGB_S3TC_DXT1,
+            &CompressedFormat::RgbaS3tcDxt1 => COMPRESSED_RGBA_S3TC_DXT1,
+            &CompressedFormat::RgbaS3tcDxt3 => COMPRESSED_RGBA_S3TC_DXT3,
+            &CompressedFormat::RgbaS3tcDxt5 => COMPRESSED_RGBA_S3TC_DXT5,
+
+            &CompressedFormat::RedRgtc1 => gl::COMPRESSED_RED_RGTC1,
+            &CompressedFormat::SignedRedRgtc1 => gl::COMPRESSED_SIGNED_RED_RGTC1,
+            &CompressedFormat::RgRgtc2 => gl::COMPRESSED_RG_RGTC2,
+            &CompressedFormat::SignedRgRgtc2 => gl::COMPRESSED_SIGNED_RG_RGTC2,
+
+            &CompressedFormat::RgbBptcSignedFloat => gl::COMPRESSED_RGB_BPTC_SIGNED_FLOAT,
+            &CompressedFormat::RgbBptcUnsignedFloat => gl::COMPRESSED_RGB_BPTC_UNSIGNED_FLOAT,
+            &CompressedFormat::RgbaBptcUnorm => gl::COMPRESSED_RGBA_BPTC_UNORM,
+            &CompressedFormat::SrgbAlphaBptcUnorm => gl::COMPRESSED_SRGB_ALPHA_BPTC_UNORM,
+
+            &CompressedFormat::Rgb8Etc2 => gl::COMPRESSED_RGB8_ETC2,
+            &CompressedFormat::Srgb8Etc2 => gl::COMPRESSED_SRGB8_ETC2,
+            &CompressedFormat::Rgb8PunchthroughAlpha1Etc2 => gl::COMPRESSED_RGB8_PUNCHTHROUGH_ALPHA1_ETC2,
+            &CompressedFormat::Rgba8Etc2Eac => gl::COMPRESSED_RGBA8_ETC2_EAC,
+            &CompressedFormat::Srgb8Alpha8Etc2Eac => gl::COMPRESSED_SRGB8_ALPHA8_ETC2_EAC,
+            &CompressedFormat::R11Eac => gl::COMPRESSED_R11_EAC,
+            &CompressedFormat::SignedR11Eac => gl::COMPRESSED_SIGNED_R11_EAC,
+            &CompressedFormat::Rg11Eac => gl::COMPRESSED_RG11_EAC,
+            &CompressedFormat::SignedRg11Eac => gl::COMPRESSED_SIGNED_RG11_EAC,
+
+            &CompressedFormat::RgbaAstc4x4 => COMPRESSED_RGBA_ASTC_4X4,
+            &CompressedFormat::RgbaAstc5x5 => COMPRESSED_RGBA_ASTC_5X5,
+            &CompressedFormat::RgbaAstc6x6 => COMPRESSED_RGBA_ASTC_6X6,
+            &CompressedFormat::RgbaAstc8x8 => COMPRESSED_RGBA_ASTC_8X8,
+            &CompressedFormat::RgbaAstc10x10 => COMPRESSED_RGBA_ASTC_10X10,
+            &CompressedFormat::RgbaAstc12x12 => COMPRESSED_RGBA_ASTC_12X12,
+        }
+    }
+
+    #[inline]
+    pub fn from_gl(format: GLenum) -> Option<Self> {
+        match format {
+            COMPRESSED_RGB_S3TC_DXT1 => Some(CompressedFormat::RgbS3tcDxt1),
+            COMPRESSED_RGBA_S3TC_DXT1 => Some(CompressedFormat::RgbaS3tcDxt1),
+            COMPRESSED_RGBA_S3TC_DXT3 => Some(CompressedFormat::RgbaS3tcDxt3),
+            COMPRESSED_RGBA_S3TC_DXT5 => Some(CompressedFormat::RgbaS3tcDxt5),
+
+            gl::COMPRESSED_RED_RGTC1 => Some(CompressedFormat::RedRgtc1),
+            gl::COMPRESSED_SIGNED_RED_RGTC1 => Some(CompressedFormat::SignedRedRgtc1),
+            gl::COMPRESSED_RG_RGTC2 => Some(CompressedFormat::RgRgtc2),
+            gl::COMPRESSED_SIGNED_RG_RGTC2 => Some(CompressedFormat::SignedRgRgtc2),
+
+            gl::COMPRESSED_RGB_BPTC_SIGNED_FLOAT => Some(CompressedFormat::RgbBptcSignedFloat),
+            gl::COMPRESSED_RGB_BPTC_UNSIGNED_FLOAT => Some(CompressedFormat::RgbBptcUnsignedFloat),
+            gl::COMPRESSED_RGBA_BPTC_UNORM => Some(CompressedFormat::RgbaBptcUnorm),
+            gl::COMPRESSED_SRGB_ALPHA_BPTC_UNORM => Some(CompressedFormat::SrgbAlphaBptcUnorm),
+
+            gl::COMPRESSED_RGB8_ETC2 => Some(CompressedFormat::Rgb8Etc2),
+            gl::COMPRESSED_SRGB8_ETC2 => Some(CompressedFormat::Srgb8Etc2),
+            gl::COMPRESSED_RGB8_PUNCHTHROUGH_ALPHA1_ETC2 => Some(CompressedFormat::Rgb8PunchthroughAlpha1Etc2),
+            gl::COMPRESSED_RGBA8_ETC2_EAC => Some(CompressedFormat::Rgba8Etc2Eac),
+            gl::COMPRESSED_SRGB8_ALPHA8_ETC2_EAC => Some(CompressedFormat::Srgb8Alpha8Etc2Eac),
+            gl::COMPRESSED_R11_EAC => Some(CompressedFormat::R11Eac),
+            gl::COMPRESSED_SIGNED_R11_EAC => Some(CompressedFormat::SignedR11Eac),
+            gl::COMPRESSED_RG11_EAC => Some(CompressedFormat::Rg11Eac),
+            gl::COMPRESSED_SIGNED_RG11_EAC => Some(CompressedFormat::SignedRg11Eac),
+
+            COMPRESSED_RGBA_ASTC_4X4 => Some(CompressedFormat::RgbaAstc4x4),
+            COMPRESSED_RGBA_ASTC_5X5 => Some(CompressedFormat::RgbaAstc5x5),
+            COMPRESSED_RGBA_ASTC_6X6 => Some(CompressedFormat::RgbaAstc6x6),
+            COMPRESSED_RGBA_ASTC_8X8 => Some(CompressedFormat::RgbaAstc8x8),
+            COMPRESSED_RGBA_ASTC_10X10 => Some(CompressedFormat::RgbaAstc10x10),
+            COMPRESSED_RGBA_ASTC_12X12 => Some(CompressedFormat::RgbaAstc12x12),
+
+            _ => None,
+        }
+    }
+
+    #[inline]
+    pub fn block_size(&self) -> (usize, usize) {
+        match self {
+            &CompressedFormat::RgbaAstc5x5 => (5, 5),
+            &CompressedFormat::RgbaAstc6x6 => (6, 6),
+            &CompressedFormat::RgbaAstc8x8 => (8, 8),
+            &CompressedFormat::RgbaAstc10x10 => (10, 10),
+            &CompressedFormat::RgbaAstc12x12 => (12, 12),
+            _ => (4, 4),
+        }
+    }
+
+    #[inline]
+    pub fn block_bytes(&self) -> usize {
+        match self {
+            &CompressedFormat::RgbS3tcDxt1 |
+            &CompressedFormat::RgbaS3tcDxt1 |
+            &CompressedFormat::RedRgtc1 |
+            &CompressedFormat::SignedRedRgtc1 |
+            &CompressedFormat::Rgb8Etc2 |
+            &CompressedFormat::Srgb8Etc2 |
+            &CompressedFormat::Rgb8PunchthroughAlpha1Etc2 |
+            &CompressedFormat::R11Eac |
+            &CompressedFormat::SignedR11Eac => 8,
+            _ => 16,
+        }
+    }
+
+    #[inline]
+    pub fn data_size(&self, width: usize, height: usize) -> usize {
+        let (block_width, block_height) = self.block_size();
+        let blocks_x = (width + block_width - 1) / block_width;
+        let blocks_y = (height + block_height - 1) / block_height;
+        blocks_x * blocks_y * self.block_bytes()
+    }
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub enum DebugSource {
+    Api,
+    WindowSystem,
+    ShaderCompiler,
+    ThirdParty,
+    Application,
+    Other,
+    DontCare,
+}
+
+impl DebugSource {
+    #[inline]
+    pub fn to_gl(&self) -> GLenum {
+        match self {
+            &DebugSource::Api => gl::DEBUG_SOURCE_API,
+            &DebugSource::WindowSystem => gl::DEBUG_SOURCE_WINDOW_SYSTEM,
+            &DebugSource::ShaderCompiler => gl::DEBUG_SOURCE_SHADER_COMPILER,
+            &DebugSource::ThirdParty => gl::DEBUG_SOURCE_THIRD_PARTY,
+            &DebugSource::Application => gl::DEBUG_SOURCE_APPLICATION,
+            &DebugSource::Other => gl::DEBUG_SOURCE_OTHER,
+            &DebugSource::DontCare => gl::DONT_CARE,
+        }
+    }
+    #[inline]
+    pub fn from_gl(value: GLenum) -> Self {
+        match value {
+            gl::DEBUG_SOURCE_API => DebugSource::Api,
+            gl::DEBUG_SOURCE_WINDOW_SYSTEM => DebugSource::WindowSystem,
+            gl::DEBUG_SOURCE_SHADER_COMPILER => DebugSource::ShaderCompiler,
+            gl::DEBUG_SOURCE_THIRD_PARTY => DebugSource::ThirdParty,
+            gl::DEBUG_SOURCE_APPLICATION => DebugSource::Application,
+            gl::DONT_CARE => DebugSource::DontCare,
+            _ => DebugSource::Other,
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub enum DebugType {
+    Error,
+    DeprecatedBehavior,
+    UndefinedBehavior,
+    Portability,
+    Performance,
+    Marker,
+    PushGroup,
+    PopGroup,
+    Other,
+    DontCare,
+}
+
+impl DebugType {
+    #[inline]
+    pub fn to_gl(&self) -> GLenum {
+        match self {
+            &DebugType::Error => gl::DEBUG_TYPE_ERROR,
+            &DebugType::DeprecatedBehavior => gl::DEBUG_TYPE_DEPRECATED_BEHAVIOR,
+            &DebugType::UndefinedBehavior => gl::DEBUG_TYPE_UNDEFINED_BEHAVIOR,
+            &DebugType::Portability => gl::DEBUG_TYPE_PORTABILITY,
+            &DebugType::Performance => gl::DEBUG_TYPE_PERFORMANCE,
+            &DebugType::Marker => gl::DEBUG_TYPE_MARKER,
+            &DebugType::PushGroup => gl::DEBUG_TYPE_PUSH_GROUP,
+            &DebugType::PopGroup => gl::DEBUG_TYPE_POP_GROUP,
+            &DebugType::Other => gl::DEBUG_TYPE_OTHER,
+            &DebugType::DontCare => gl::DONT_CARE,
+        }
+    }
+    #[inline]
+    pub fn from_gl(value: GLenum) -> Self {
+        match value {
+            gl::DEBUG_TYPE_ERROR => DebugType::Error,
+            gl::DEBUG_TYPE_DEPRECATED_BEHAVIOR => DebugType::DeprecatedBehavior,
+            gl::DEBUG_TYPE_UNDEFINED_BEHAVIOR => DebugType::UndefinedBehavior,
+            gl::DEBUG_TYPE_PORTABILITY => DebugType::Portability,
+            gl::DEBUG_TYPE_PERFORMANCE => DebugType::Performance,
+            gl::DEBUG_TYPE_MARKER => DebugType::Marker,
+            gl::DEBUG_TYPE_PUSH_GROUP => DebugType::PushGroup,
+            gl::DEBUG_TYPE_POP_GROUP => DebugType::PopGroup,
+            gl::DONT_CARE => DebugType::DontCare,
+            _ => DebugType::Other,
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub enum DebugSeverity {
+    High,
+    Medium,
+    Low,
+    Notification,
+    DontCare,
+}
+
+impl DebugSeverity {
+    #[inline]
+    pub fn to_gl(&self) -> GLenum {
+        match self {
+            &DebugSeverity::High => gl::DEBUG_SEVERITY_HIGH,
+            &DebugSeverity::Medium => gl::DEBUG_SEVERITY_MEDIUM,
+            &DebugSeverity::Low => gl::DEBUG_SEVERITY_LOW,
+            &DebugSeverity::Notification => gl::DEBUG_SEVERITY_NOTIFICATION,
+            &DebugSeverity::DontCare => gl::DONT_CARE,
+        }
+    }
+    #[inline]
+    pub fn from_gl(value: GLenum) -> Self {
+        match value {
+            gl::DEBUG_SEVERITY_HIGH => DebugSeverity::High,
+            gl::DEBUG_SEVERITY_MEDIUM => DebugSeverity::Medium,
+            gl::DEBUG_SEVERITY_LOW => DebugSeverity::Low,
+            gl::DEBUG_SEVERITY_NOTIFICATION => DebugSeverity::Notification,
+            _ => DebugSeverity::DontCare,
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+
+    #[test]
+    fn test_framebuffer_status_from_gl() {
+        let statuses = [
+            FramebufferStatus::Complete,
+            FramebufferStatus::Undefined,
+            FramebufferStatus::IncompleteAttachment,
+            FramebufferStatus::IncompleteMissingAttachment,
+            FramebufferStatus::IncompleteDrawBuffer,
+            FramebufferStatus::IncompleteReadBuffer,
+            FramebufferStatus::Unsupported,
+            FramebufferStatus::IncompleteMultisample,
+            FramebufferStatus::IncompleteLayerTargets,
+        ];
+        for status in statuses.iter() {
+            assert_eq!(FramebufferStatus::from_gl(status.to_gl()), *status);
+        }
+
+        assert_eq!(FramebufferStatus::from_gl(gl::FRAMEBUFFER_COMPLETE), FramebufferStatus::Complete);
+        assert_eq!(FramebufferStatus::from_gl(0x1234), FramebufferStatus::Unknown(0x1234));
+        assert_eq!(FramebufferStatus::Unknown(0x1234).to_gl(), 0x1234);
+    }
+
+    #[test]
+    fn test_internal_format_kinds() {
+        assert_eq!(InternalFormat::RGBA8.kinds(), &[TextureKind::UnsignedByte]);
+        assert_eq!(InternalFormat::RGBA16F.kinds(), &[TextureKind::HalfFloat, TextureKind::Float]);
+        assert_eq!(InternalFormat::DepthComponent24.kinds(), &[TextureKind::UnsignedInt]);
+        assert_eq!(InternalFormat::Depth32FStencil8.kinds(), &[TextureKind::Float32UnsignedInt248Rev]);
+        assert!(InternalFormat::RGBA.kinds().contains(&TextureKind::UnsignedShort5551));
+        assert!(!InternalFormat::RGB.kinds().contains(&TextureKind::Float));
+    }
+
+    #[test]
+    fn test_internal_format_properties() {
+        assert!(!InternalFormat::RGBA.is_sized());
+        assert!(InternalFormat::RGBA8.is_sized());
+        assert!(!InternalFormat::RGBA8.requires_gl3());
+        assert!(InternalFormat::RG8.requires_gl3());
+        assert!(InternalFormat::RGBA32UI.is_integer());
+        assert!(InternalFormat::Depth24Stencil8.is_depth());
+        assert!(InternalFormat::Depth24Stencil8.is_stencil());
+        assert!(!InternalFormat::DepthComponent16.is_stencil());
+        assert_eq!(InternalFormat::SRGB8Alpha8.format(), TextureFormat::RGBA);
+        assert_eq!(InternalFormat::R16I.format(), TextureFormat::RedInteger);
+    }
+
+    #[test]
+    fn test_internal_format_from_format() {
+        assert_eq!(InternalFormat::from_format(TextureFormat::BGRA, TextureKind::UnsignedByte), InternalFormat::RGBA);
+        assert_eq!(InternalFormat::from_format(TextureFormat::RedInteger, TextureKind::Short), InternalFormat::R16I);
+        assert_eq!(
+            InternalFormat::from_format(TextureFormat::RGBAInteger, TextureKind::UnsignedInt2101010Rev),
+            InternalFormat::RGB10A2UI
+        );
+        assert_eq!(InternalFormat::from_format(TextureFormat::DepthComponent, TextureKind::UnsignedInt), InternalFormat::DepthComponent);
+    }
+
+    #[test]
+    fn test_debug_source_from_gl() {
+        let sources = [
+            DebugSource::Api,
+            DebugSource::WindowSystem,
+            DebugSource::ShaderCompiler,
+            DebugSource::ThirdParty,
+            DebugSource::Application,
+            DebugSource::Other,
+            DebugSource::DontCare,
+        ];
+        for source in sources.iter() {
+            assert_eq!(DebugSource::from_gl(source.to_gl()), *source);
+        }
+        assert_eq!(DebugSource::from_gl(0x1234), DebugSource::Other);
+    }
+
+    #[test]
+    fn test_debug_type_from_gl() {
+        let kinds = [
+            DebugType::Error,
+            DebugType::DeprecatedBehavior,
+            DebugType::UndefinedBehavior,
+            DebugType::Portability,
+            DebugType::Performance,
+            DebugType::Marker,
+            DebugType::PushGroup,
+            DebugType::PopGroup,
+            DebugType::Other,
+            DebugType::DontCare,
+        ];
+        for kind in kinds.iter() {
+            assert_eq!(DebugType::from_gl(kind.to_gl()), *kind);
+        }
+        assert_eq!(DebugType::from_gl(0x1234), DebugType::Other);
+    }
+
+    #[test]
+    fn test_debug_severity_from_gl() {
+        let severities = [
+            DebugSeverity::High,
+            DebugSeverity::Medium,
+            DebugSeverity::Low,
+            DebugSeverity::Notification,
+            DebugSeverity::DontCare,
+        ];
+        for severity in severities.iter() {
+            assert_eq!(DebugSeverity::from_gl(severity.to_gl()), *severity);
+        }
+        assert_eq!(DebugSeverity::from_gl(0x1234), DebugSeverity::DontCare);
+    }
+}
@@ -0,0 +1,195 @@
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use core::{fmt, mem, ptr};
+use core::sync::atomic::{AtomicPtr, Ordering};
+
+use gl;
+use gl::types::*;
+
+use enums::{CompressedFormat, FramebufferStatus};
+
+
+static MAX_DRAINED_ERRORS: usize = 32;
+
+static ERROR_HANDLER: AtomicPtr<()> = AtomicPtr::new(ptr::null_mut());
+
+
+pub type GlErrorHandler = fn(&'static str, &[GlError]);
+
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub enum GlError {
+    InvalidEnum,
+    InvalidValue,
+    InvalidOperation,
+    InvalidFramebufferOperation,
+    OutOfMemory,
+    StackOverflow,
+    StackUnderflow,
+    Unknown(GLenum),
+}
+
+impl GlError {
+    #[inline]
+    pub fn from_gl(error: GLenum) -> Option<Self> {
+        match error {
+            gl::NO_ERROR => None,
+            gl::INVALID_ENUM => Some(GlError::InvalidEnum),
+            gl::INVALID_VALUE => Some(GlError::InvalidValue),
+            gl::INVALID_OPERATION => Some(GlError::InvalidOperation),
+            gl::INVALID_FRAMEBUFFER_OPERATION => Some(GlError::InvalidFramebufferOperation),
+            gl::OUT_OF_MEMORY => Some(GlError::OutOfMemory),
+            gl::STACK_OVERFLOW => Some(GlError::StackOverflow),
+            gl::STACK_UNDERFLOW => Some(GlError::StackUnderflow),
+            error => Some(GlError::Unknown(error)),
+        }
+    }
+
+    #[inline]
+    pub fn to_gl(&self) -> GLenum {
+        match self {
+            &GlError::InvalidEnum => gl::INVALID_ENUM,
+            &GlError::InvalidValue => gl::INVALID_VALUE,
+            &GlError::InvalidOperation => gl::INVALID_OPERATION,
+            &GlError::InvalidFramebufferOperation => gl::INVALID_FRAMEBUFFER_OPERATION,
+            &GlError::OutOfMemory => gl::OUT_OF_MEMORY,
+            &GlError::StackOverflow => gl::STACK_OVERFLOW,
+            &GlError::StackUnderflow => gl::STACK_UNDERFLOW,
+            &GlError::Unknown(error) => error,
+        }
+    }
+}
+
+impl fmt::Display for GlError {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            &GlError::InvalidEnum => write!(f, "GL_INVALID_ENUM"),
+            &GlError::InvalidValue => write!(f, "GL_INVALID_VALUE"),
+            &GlError::InvalidOperation => write!(f, "GL_INVALID_OPERATION"),
+            &GlError::InvalidFramebufferOperation => write!(f, "GL_INVALID_FRAMEBUFFER_OPERATION"),
+            &GlError::OutOfMemory => write!(f, "GL_OUT_OF_MEMORY"),
+            &GlError::StackOverflow => write!(f, "GL_STACK_OVERFLOW"),
+            &GlError::StackUnderflow => write!(f, "GL_STACK_UNDERFLOW"),
+            &GlError::Unknown(error) => write!(f, "unknown GL error 0x{:X}", error),
+        }
+    }
+}
+
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub enum LoadError {
+    InvalidIdentifier,
+    Truncated,
+    UnsupportedFormat(u32),
+    UnsupportedLayout,
+    Supercompressed(u32),
+    UnsupportedByContext(CompressedFormat),
+}
+
+impl fmt::Display for LoadError {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            &LoadError::InvalidIdentifier => write!(f, "file identifier does not match the container format"),
+            &LoadError::Truncated => write!(f, "file is shorter than its header describes"),
+            &LoadError::UnsupportedFormat(format) => write!(f, "unsupported pixel format 0x{:X}", format),
+            &LoadError::UnsupportedLayout => write!(f, "only single 2D images with optional mipmaps are supported"),
+            &LoadError::Supercompressed(scheme) => write!(f, "unsupported supercompression scheme {}", scheme),
+            &LoadError::UnsupportedByContext(format) => write!(f, "{:?} is not supported by this context", format),
+        }
+    }
+}
+
+
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct FramebufferError {
+    status: FramebufferStatus,
+    explanation: String,
+}
+
+impl FramebufferError {
+    #[inline(always)]
+    pub fn new(status: FramebufferStatus, explanation: String) -> Self {
+        FramebufferError {
+            status: status,
+            explanation: explanation,
+        }
+    }
+
+    #[inline(always)]
+    pub fn status(&self) -> FramebufferStatus { self.status }
+    #[inline(always)]
+    pub fn explanation(&self) -> &str { &self.explanation }
+}
+
+impl fmt::Display for FramebufferError {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:?}: {}", self.status, self.explanation)
+    }
+}
+
+
+#[inline(always)]
+pub fn global_error_handler_set() -> bool {
+    !ERROR_HANDLER.load(Ordering::Relaxed).is_null()
+}
+// The handler is shared by every context in the process, gl_check! runs in
+// places without a Context such as Drop.
+#[inline]
+pub fn set_global_error_handler(handler: Option<GlErrorHandler>) {
+    let handler = match handler {
+        Some(handler) => {
+            drain_errors();
+            handler as *mut ()
+        },
+        None => ptr::null_mut(),
+    };
+    ERROR_HANDLER.store(handler, Ordering::Relaxed);
+}
+#[inline(always)]
+fn error_handler() -> Option<GlErrorHandler> {
+    let handler = ERROR_HANDLER.load(Ordering::Relaxed);
+
+    if handler.is_null() {
+        None
+    } else {
+        Some(unsafe { mem::transmute::<*mut (), GlErrorHandler>(handler) })
+    }
+}
+
+// glGetError only reports one flag per call, so keep asking until it says
+// GL_NO_ERROR. The cap guards against drivers that never clear a lost context.
+#[inline]
+pub fn drain_errors() -> Vec<GlError> {
+    let mut errors = Vec::new();
+
+    while errors.len() < MAX_DRAINED_ERRORS {
+        match GlError::from_gl(unsafe { gl::GetError() }) {
+            Some(error) => errors.push(error),
+            None => break,
+        }
+    }
+
+    errors
+}
+
+#[inline]
+pub fn check_errors(function: &'static str) {
+    if let Some(handler) = error_handler() {
+        let errors = drain_errors();
+
+        if !errors.is_empty() {
+            handler(function, &errors);
+        }
+    }
+}
+
+
+macro_rules! gl_check {
+    ($function: expr) => (
+        $crate::error::check_errors($function)
+    );
+}
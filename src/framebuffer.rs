@@ -1,17 +1,134 @@
+use alloc::string::String;
 use alloc::vec::Vec;
 
+use core::fmt::Write;
+use core::mem;
 use core::ops::Drop;
 
 use gl;
 use gl::types::*;
 
 use context::Context;
-use texture::Texture;
-use enums::Attachment;
+use debug;
+use error::FramebufferError;
+use renderbuffer::Renderbuffer;
+use texture::{null_upload_format, ImageOptions, Texture};
+use enums::{Attachment, CubeFace, FilterMode, FramebufferStatus, InternalFormat, StoreAction, TextureWrap};
+
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct FramebufferAttachment {
+    attachment: Attachment,
+    target: GLenum,
+    id: GLuint,
+    width: usize,
+    height: usize,
+    samples: usize,
+    format: InternalFormat,
+    level: usize,
+    layer: Option<usize>,
+}
+
+impl FramebufferAttachment {
+    #[inline(always)]
+    pub fn attachment(&self) -> Attachment { self.attachment }
+    #[inline(always)]
+    pub fn target(&self) -> GLenum { self.target }
+    #[inline(always)]
+    pub fn id(&self) -> GLuint { self.id }
+    #[inline(always)]
+    pub fn width(&self) -> usize { self.width }
+    #[inline(always)]
+    pub fn height(&self) -> usize { self.height }
+    #[inline(always)]
+    pub fn samples(&self) -> usize { self.samples }
+    #[inline(always)]
+    pub fn format(&self) -> InternalFormat { self.format }
+    #[inline(always)]
+    pub fn level(&self) -> usize { self.level }
+    #[inline(always)]
+    pub fn layer(&self) -> Option<usize> { self.layer }
+}
+
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct AttachmentDescriptor {
+    attachment: Attachment,
+    format: InternalFormat,
+    renderbuffer: bool,
+    samples: usize,
+    wrap: TextureWrap,
+    filter: FilterMode,
+    store_action: StoreAction,
+}
+
+impl AttachmentDescriptor {
+    #[inline(always)]
+    pub fn new(attachment: Attachment, format: InternalFormat) -> Self {
+        AttachmentDescriptor {
+            attachment: attachment,
+            format: format,
+            renderbuffer: false,
+            samples: 0,
+            wrap: TextureWrap::Clamp,
+            filter: FilterMode::Linear,
+            store_action: StoreAction::Store,
+        }
+    }
+
+    #[inline(always)]
+    pub fn attachment(&self) -> Attachment { self.attachment }
+    #[inline(always)]
+    pub fn format(&self) -> InternalFormat { self.format }
+    #[inline(always)]
+    pub fn renderbuffer(&self) -> bool { self.renderbuffer }
+    #[inline(always)]
+    pub fn samples(&self) -> usize { self.samples }
+    #[inline(always)]
+    pub fn wrap(&self) -> TextureWrap { self.wrap }
+    #[inline(always)]
+    pub fn filter(&self) -> FilterMode { self.filter }
+    #[inline(always)]
+    pub fn store_action(&self) -> StoreAction { self.store_action }
+
+    #[inline(always)]
+    pub fn set_renderbuffer(&mut self, renderbuffer: bool) -> &mut Self {
+        self.renderbuffer = renderbuffer;
+        self
+    }
+    #[inline(always)]
+    pub fn set_samples(&mut self, samples: usize) -> &mut Self {
+        self.samples = samples;
+        self
+    }
+    #[inline(always)]
+    pub fn set_wrap(&mut self, wrap: TextureWrap) -> &mut Self {
+        self.wrap = wrap;
+        self
+    }
+    #[inline(always)]
+    pub fn set_filter(&mut self, filter: FilterMode) -> &mut Self {
+        self.filter = filter;
+        self
+    }
+    #[inline(always)]
+    pub fn set_store_action(&mut self, store_action: StoreAction) -> &mut Self {
+        self.store_action = store_action;
+        self
+    }
+}
 
 
 pub struct Framebuffer {
     id: GLuint,
+    draw_buffers: Vec<Attachment>,
+    attachments: Vec<FramebufferAttachment>,
+
+    width: usize,
+    height: usize,
+    descriptors: Vec<AttachmentDescriptor>,
+    textures: Vec<(Attachment, Texture)>,
+    renderbuffers: Vec<(Attachment, Renderbuffer)>,
 }
 
 impl Drop for Framebuffer {
@@ -19,6 +136,7 @@ impl Drop for Framebuffer {
     fn drop(&mut self) {
         if self.id != 0 {
             unsafe { gl::DeleteFramebuffers(1, &self.id); }
+            gl_check!("Framebuffer::drop");
         }
     }
 }
@@ -30,13 +148,334 @@ impl Framebuffer {
             id: {
                 let mut id = 0;
                 unsafe { gl::GenFramebuffers(1, &mut id); }
+                gl_check!("Framebuffer::new");
                 id
             },
+            draw_buffers: Vec::new(),
+            attachments: Vec::new(),
+
+            width: 0,
+            height: 0,
+            descriptors: Vec::new(),
+            textures: Vec::new(),
+            renderbuffers: Vec::new(),
         }
     }
+
+    #[inline]
+    pub fn from_descriptors(
+        context: &mut Context,
+        width: usize,
+        height: usize,
+        descriptors: &[AttachmentDescriptor]
+    ) -> Result<Self, FramebufferError> {
+        for descriptor in descriptors {
+            let result = check_descriptor(
+                context.is_es(), context.major(), |name| context.has_extenstion(name),
+                context.max_color_attachments(), context.has_multisample_textures(), descriptor
+            );
+            if let Err(explanation) = result {
+                return Err(FramebufferError::new(FramebufferStatus::Unsupported, explanation));
+            }
+        }
+
+        let mut framebuffer = Framebuffer::new();
+        framebuffer.descriptors.extend_from_slice(descriptors);
+
+        for descriptor in descriptors {
+            if descriptor.renderbuffer {
+                framebuffer.renderbuffers.push((descriptor.attachment, Renderbuffer::new()));
+            } else {
+                framebuffer.textures.push((descriptor.attachment, Texture::new()));
+            }
+        }
+
+        framebuffer.resize(context, width, height)?;
+        Ok(framebuffer)
+    }
     #[inline(always)]
     pub fn id(&self) -> GLuint { self.id }
 
+    #[inline]
+    pub fn set_label(&self, context: &Context, label: &str) -> &Self {
+        debug::object_label(context, gl::FRAMEBUFFER, self.id, label);
+        self
+    }
+
+    #[inline(always)]
+    pub fn draw_buffers(&self) -> &[Attachment] { &self.draw_buffers }
+
+    #[inline(always)]
+    pub fn width(&self) -> usize { self.width }
+    #[inline(always)]
+    pub fn height(&self) -> usize { self.height }
+    #[inline(always)]
+    pub fn descriptors(&self) -> &[AttachmentDescriptor] { &self.descriptors }
+
+    #[inline]
+    pub fn texture(&self, attachment: Attachment) -> Option<&Texture> {
+        self.textures.iter().find(|&&(a, _)| a == attachment).map(|&(_, ref texture)| texture)
+    }
+    #[inline]
+    pub fn renderbuffer(&self, attachment: Attachment) -> Option<&Renderbuffer> {
+        self.renderbuffers.iter().find(|&&(a, _)| a == attachment).map(|&(_, ref renderbuffer)| renderbuffer)
+    }
+
+    #[inline]
+    pub fn resize(&mut self, context: &mut Context, width: usize, height: usize) -> Result<(), FramebufferError> {
+        let mut textures = mem::replace(&mut self.textures, Vec::new());
+        let mut renderbuffers = mem::replace(&mut self.renderbuffers, Vec::new());
+        let descriptors = self.descriptors.clone();
+
+        for descriptor in descriptors.iter() {
+            let attachment = descriptor.attachment;
+            let format = descriptor.format;
+
+            if descriptor.renderbuffer {
+                if let Some(&mut (_, ref mut renderbuffer)) = renderbuffers.iter_mut().find(|&&mut (a, _)| a == attachment) {
+                    let mut new_renderbuffer = Renderbuffer::new();
+                    new_renderbuffer.set_storage(context, format, width, height, descriptor.samples);
+                    self.attach_renderbuffer(context, attachment, &new_renderbuffer);
+                    *renderbuffer = new_renderbuffer;
+                }
+            } else if let Some(&mut (_, ref mut texture)) = textures.iter_mut().find(|&&mut (a, _)| a == attachment) {
+                if texture.is_immutable() {
+                    let mut new_texture = Texture::new();
+                    allocate_texture(context, &mut new_texture, descriptor, width, height);
+                    self.attach_texture(context, attachment, &new_texture, 0);
+                    *texture = new_texture;
+                } else {
+                    allocate_texture(context, texture, descriptor, width, height);
+                    self.attach_texture(context, attachment, texture, 0);
+                }
+            }
+        }
+
+        self.textures = textures;
+        self.renderbuffers = renderbuffers;
+        self.width = width;
+        self.height = height;
+
+        self.check(context)
+    }
+    #[inline(always)]
+    pub fn attachments(&self) -> &[FramebufferAttachment] { &self.attachments }
+
+    #[inline]
+    pub fn status(&self, context: &mut Context) -> FramebufferStatus {
+        context.set_framebuffer(self, false);
+        let status = unsafe { gl::CheckFramebufferStatus(gl::FRAMEBUFFER) };
+        gl_check!("Framebuffer::status");
+        FramebufferStatus::from_gl(status)
+    }
+
+    #[inline]
+    pub fn check(&self, context: &mut Context) -> Result<(), FramebufferError> {
+        match self.status(context) {
+            FramebufferStatus::Complete => Ok(()),
+            status => Err(FramebufferError::new(status, self.explain(status))),
+        }
+    }
+
+    #[inline(always)]
+    fn explain(&self, status: FramebufferStatus) -> String {
+        explain_status(status, &self.attachments, &self.draw_buffers)
+    }
+
+    #[inline]
+    pub fn invalidate(&self, context: &mut Context, attachments: &[Attachment]) -> &Self {
+        if attachments.is_empty() || !context.has_invalidate_framebuffer() {
+            return self;
+        }
+
+        let gl_enums = attachments_to_gl(attachments);
+
+        context.set_framebuffer(self, false);
+        unsafe { gl::InvalidateFramebuffer(gl::FRAMEBUFFER, gl_enums.len() as GLsizei, gl_enums.as_ptr()); }
+        gl_check!("Framebuffer::invalidate");
+
+        self
+    }
+
+    #[inline]
+    pub fn invalidate_sub_region(
+        &self, context: &mut Context, attachments: &[Attachment], x: usize, y: usize, width: usize, height: usize
+    ) -> &Self {
+        if attachments.is_empty() || !context.has_invalidate_framebuffer() {
+            return self;
+        }
+
+        let gl_enums = attachments_to_gl(attachments);
+
+        context.set_framebuffer(self, false);
+        unsafe {
+            gl::InvalidateSubFramebuffer(
+                gl::FRAMEBUFFER, gl_enums.len() as GLsizei, gl_enums.as_ptr(),
+                x as GLint, y as GLint, width as GLsizei, height as GLsizei
+            );
+        }
+        gl_check!("Framebuffer::invalidate_sub_region");
+
+        self
+    }
+
+    #[inline]
+    pub fn invalidate_discarded(&self, context: &mut Context) -> &Self {
+        let attachments: Vec<Attachment> = self.descriptors.iter()
+            .filter(|descriptor| descriptor.store_action == StoreAction::Discard)
+            .map(|descriptor| descriptor.attachment)
+            .collect();
+
+        self.invalidate(context, &attachments)
+    }
+
+    #[inline]
+    fn record_attachment(&mut self, attachment: FramebufferAttachment) {
+        record_attachment(&mut self.attachments, attachment);
+    }
+
+    #[inline]
+    pub fn attach_texture(&mut self, context: &mut Context, attachment: Attachment, texture: &Texture, level: usize) -> &mut Self {
+        check_attachment(context, attachment);
+        context.set_framebuffer(self, false);
+
+        let kind = texture.kind();
+
+        unsafe {
+            if kind == gl::TEXTURE_2D || kind == gl::TEXTURE_2D_MULTISAMPLE {
+                gl::FramebufferTexture2D(gl::FRAMEBUFFER, attachment.to_gl(), kind, texture.id(), level as GLint);
+            } else if gl::FramebufferTexture::is_loaded() {
+                gl::FramebufferTexture(gl::FRAMEBUFFER, attachment.to_gl(), texture.id(), level as GLint);
+            } else {
+                panic!("Framebuffer::attach_texture can not attach layered textures on {:?}", context.version());
+            }
+        }
+        gl_check!("Framebuffer::attach_texture");
+
+        self.record_attachment(texture_attachment(attachment, texture, level, None));
+        self.add_draw_buffer(context, attachment)
+    }
+
+    #[inline]
+    pub fn attach_texture_layer(
+        &mut self, context: &mut Context, attachment: Attachment, texture: &Texture, level: usize, layer: usize
+    ) -> &mut Self {
+        let kind = texture.kind();
+
+        if kind != gl::TEXTURE_3D && kind != gl::TEXTURE_2D_ARRAY {
+            panic!("Framebuffer::attach_texture_layer requires a 3D or 2D array texture");
+        }
+        if layer >= texture.depth() {
+            panic!("Layer {:?} is out of range for texture with {:?} layers", layer, texture.depth());
+        }
+
+        check_attachment(context, attachment);
+        context.set_framebuffer(self, false);
+
+        unsafe {
+            gl::FramebufferTextureLayer(
+                gl::FRAMEBUFFER, attachment.to_gl(), texture.id(), level as GLint, layer as GLint
+            );
+        }
+        gl_check!("Framebuffer::attach_texture_layer");
+
+        self.record_attachment(texture_attachment(attachment, texture, level, Some(layer)));
+        self.add_draw_buffer(context, attachment)
+    }
+
+    #[inline]
+    pub fn attach_cube_face(
+        &mut self, context: &mut Context, attachment: Attachment, texture: &Texture, face: CubeFace, level: usize
+    ) -> &mut Self {
+        if texture.kind() != gl::TEXTURE_CUBE_MAP {
+            panic!("Framebuffer::attach_cube_face requires a cube map texture");
+        }
+
+        check_attachment(context, attachment);
+        context.set_framebuffer(self, false);
+
+        unsafe {
+            gl::FramebufferTexture2D(gl::FRAMEBUFFER, attachment.to_gl(), face.to_gl(), texture.id(), level as GLint);
+        }
+        gl_check!("Framebuffer::attach_cube_face");
+
+        let layer = (face.to_gl() - gl::TEXTURE_CUBE_MAP_POSITIVE_X) as usize;
+        self.record_attachment(texture_attachment(attachment, texture, level, Some(layer)));
+        self.add_draw_buffer(context, attachment)
+    }
+
+    #[inline]
+    pub fn attach_renderbuffer(&mut self, context: &mut Context, attachment: Attachment, renderbuffer: &Renderbuffer) -> &mut Self {
+        check_attachment(context, attachment);
+        context.set_framebuffer(self, false);
+
+        unsafe {
+            gl::FramebufferRenderbuffer(gl::FRAMEBUFFER, attachment.to_gl(), gl::RENDERBUFFER, renderbuffer.id());
+        }
+        gl_check!("Framebuffer::attach_renderbuffer");
+
+        self.record_attachment(FramebufferAttachment {
+            attachment: attachment,
+            target: gl::RENDERBUFFER,
+            id: renderbuffer.id(),
+            width: renderbuffer.width(),
+            height: renderbuffer.height(),
+            samples: renderbuffer.samples(),
+            format: renderbuffer.format(),
+            level: 0,
+            layer: None,
+        });
+        self.add_draw_buffer(context, attachment)
+    }
+
+    #[inline]
+    pub fn set_draw_buffers(&mut self, context: &mut Context, attachments: &[Attachment]) -> &mut Self {
+        for attachment in attachments {
+            match attachment {
+                &Attachment::Color(_) => check_attachment(context, *attachment),
+                attachment => panic!("Only color attachments can be draw buffers, got {:?}", attachment),
+            }
+        }
+
+        let gl_enums = draw_buffers_to_gl(attachments);
+        if gl_enums.len() > context.max_draw_buffers() {
+            panic!(
+                "{:?} draw buffers exceeds max draw buffers {:?}",
+                gl_enums.len(), context.max_draw_buffers()
+            );
+        }
+
+        self.draw_buffers.clear();
+        self.draw_buffers.extend_from_slice(attachments);
+        self.draw_buffers.sort();
+        self.draw_buffers.dedup();
+
+        context.set_framebuffer(self, false);
+
+        if gl::DrawBuffers::is_loaded() {
+            unsafe { gl::DrawBuffers(gl_enums.len() as GLsizei, gl_enums.as_ptr()); }
+            gl_check!("Framebuffer::set_draw_buffers");
+        }
+
+        self
+    }
+
+    #[inline]
+    pub fn draw_buffer_index(&self, attachment: Attachment) -> Option<usize> {
+        match attachment {
+            Attachment::Color(index) if self.draw_buffers.contains(&attachment) => Some(index),
+            _ => None,
+        }
+    }
+
+    #[inline]
+    fn add_draw_buffer(&mut self, context: &mut Context, attachment: Attachment) -> &mut Self {
+        match with_draw_buffer(&self.draw_buffers, attachment) {
+            Some(draw_buffers) => self.set_draw_buffers(context, &draw_buffers),
+            None => self,
+        }
+    }
+
     #[inline]
     pub fn set(&mut self, _: &Context, texture: &Texture, buffers: &[Attachment], level: GLint) {
         let texture_id = texture.id();
@@ -53,11 +492,406 @@ impl Framebuffer {
             for i in 0..gl_enums.len() {
                 gl::FramebufferTexture(gl::FRAMEBUFFER, gl_enums[i], texture_id, level);
             }
-            gl::DrawBuffers(buffers.len() as i32, gl_enums.as_ptr());
+        }
+
+        for attachment in buffers {
+            self.record_attachment(texture_attachment(*attachment, texture, level as usize, None));
+        }
+        self.draw_buffers.clear();
+        for attachment in buffers {
+            if let &Attachment::Color(_) = attachment {
+                self.draw_buffers.push(*attachment);
+            }
+        }
+        self.draw_buffers.sort();
+        self.draw_buffers.dedup();
+
+        let draw_buffers = draw_buffers_to_gl(&self.draw_buffers);
+        unsafe { gl::DrawBuffers(draw_buffers.len() as GLsizei, draw_buffers.as_ptr()); }
+        gl_check!("Framebuffer::set");
+
+        let status = FramebufferStatus::from_gl(unsafe { gl::CheckFramebufferStatus(gl::FRAMEBUFFER) });
+        if status != FramebufferStatus::Complete {
+            panic!("{}", FramebufferError::new(status, self.explain(status)));
+        }
+    }
+
+    #[inline]
+    pub fn resolve(&self, context: &mut Context, target: &Framebuffer, width: usize, height: usize) {
+        let rect = [0, 0, width, height];
+        context.blit_framebuffer(Some(self), Some(target), &rect, &rect, gl::COLOR_BUFFER_BIT, FilterMode::None);
+    }
+
+    #[inline]
+    pub fn resolve_attachment(
+        &self, context: &mut Context, target: Option<&Framebuffer>, attachment: Attachment, rect: &[usize; 4]
+    ) -> &Self {
+        match attachment {
+            Attachment::Color(_) => (),
+            _ => panic!("Framebuffer::resolve_attachment expected a color attachment, got {:?}", attachment),
+        }
+
+        let back = [gl::BACK];
+        let (draw_buffers, target_draw_buffers) = match target {
+            Some(target) => (draw_buffers_to_gl(&[attachment]), draw_buffers_to_gl(target.draw_buffers())),
+            None => (back.to_vec(), back.to_vec()),
+        };
 
-            if gl::CheckFramebufferStatus(gl::FRAMEBUFFER) != gl::FRAMEBUFFER_COMPLETE {
-                panic!("Check framebuffer status failed");
+        context.set_read_framebuffer(Some(self), false);
+        context.set_draw_framebuffer(target, false);
+
+        unsafe {
+            gl::ReadBuffer(attachment.to_gl());
+            gl::DrawBuffers(draw_buffers.len() as GLsizei, draw_buffers.as_ptr());
+        }
+        gl_check!("Framebuffer::resolve_attachment");
+
+        context.blit_framebuffer(Some(self), target, rect, rect, gl::COLOR_BUFFER_BIT, FilterMode::None);
+
+        unsafe {
+            gl::ReadBuffer(gl::COLOR_ATTACHMENT0);
+            gl::DrawBuffers(target_draw_buffers.len() as GLsizei, target_draw_buffers.as_ptr());
+        }
+        gl_check!("Framebuffer::resolve_attachment");
+
+        self
+    }
+}
+
+#[inline]
+fn check_attachment(context: &Context, attachment: Attachment) {
+    if let Attachment::Color(index) = attachment {
+        if index >= context.max_color_attachments() {
+            panic!(
+                "Color attachment {:?} exceeds max color attachments {:?}",
+                index, context.max_color_attachments()
+            );
+        }
+    }
+}
+
+#[inline]
+fn check_descriptor<F>(
+    is_es: bool,
+    major: usize,
+    has_extension: F,
+    max_color_attachments: usize,
+    multisample_textures: bool,
+    descriptor: &AttachmentDescriptor
+) -> Result<(), String>
+    where F: Fn(&str) -> bool
+{
+    let format = descriptor.format;
+    let mut explanation = String::new();
+
+    let matches_attachment = match descriptor.attachment {
+        Attachment::Color(index) => {
+            if index >= max_color_attachments {
+                let _ = write!(
+                    explanation, "color attachment {} exceeds max color attachments {}", index, max_color_attachments
+                );
+                return Err(explanation);
             }
+            !format.is_depth() && !format.is_stencil()
+        },
+        Attachment::Depth => format.is_depth(),
+        Attachment::Stencil => format.is_stencil(),
+        Attachment::DepthStencil => format.is_depth() && format.is_stencil(),
+    };
+    if !matches_attachment {
+        let _ = write!(explanation, "{:?} can not be used for {:?}", format, descriptor.attachment);
+        return Err(explanation);
+    }
+
+    if descriptor.renderbuffer {
+        if is_es && !format.is_sized() {
+            let _ = write!(explanation, "renderbuffer {:?} requires a sized format, got {:?}", descriptor.attachment, format);
+            return Err(explanation);
         }
+    } else if descriptor.samples > 0 {
+        if !multisample_textures {
+            let _ = write!(explanation, "multisample texture {:?} is not supported by this context", descriptor.attachment);
+            return Err(explanation);
+        }
+        if !format.is_sized() {
+            let _ = write!(explanation, "multisample texture {:?} requires a sized format, got {:?}", descriptor.attachment, format);
+            return Err(explanation);
+        }
+    } else if null_upload_format(is_es, major, has_extension, format).is_none() {
+        let _ = write!(explanation, "texture {:?} can not allocate {:?} on this context", descriptor.attachment, format);
+        return Err(explanation);
+    }
+
+    Ok(())
+}
+
+#[inline]
+fn allocate_texture(
+    context: &mut Context, texture: &mut Texture, descriptor: &AttachmentDescriptor, width: usize, height: usize
+) {
+    if descriptor.samples > 0 {
+        texture.set_multisample2d(context, width, height, descriptor.samples, descriptor.format, true);
+        return;
+    }
+
+    let upload = null_upload_format(
+        context.is_es(), context.major(), |name| context.has_extenstion(name), descriptor.format
+    );
+    match upload {
+        Some((internal_format, format, kind)) => {
+            let mut options = ImageOptions::new(format, kind);
+            options.set_wrap(descriptor.wrap).set_filter(descriptor.filter);
+            texture.set_sized_null2d(context, width, height, internal_format, &options);
+        },
+        None => panic!("Can not allocate {:?} on {:?}", descriptor.format, context.version()),
+    }
+}
+
+#[inline]
+fn explain_status(status: FramebufferStatus, attachments: &[FramebufferAttachment], draw_buffers: &[Attachment]) -> String {
+    let mut explanation = String::new();
+    let _ = write!(explanation, "{}", status.description());
+
+    let (width, height, samples) = match attachments.first() {
+        Some(first) => (first.width, first.height, first.samples),
+        None => (0, 0, 0),
+    };
+
+    for attachment in attachments.iter() {
+        let _ = write!(
+            explanation, "\n  {:?}: {} {} {:?} {}x{} samples {}",
+            attachment.attachment,
+            if attachment.target == gl::RENDERBUFFER {"renderbuffer"} else {"texture"},
+            attachment.id, attachment.format, attachment.width, attachment.height, attachment.samples
+        );
+        if attachment.level != 0 {
+            let _ = write!(explanation, " level {}", attachment.level);
+        }
+        if let Some(layer) = attachment.layer {
+            let _ = write!(explanation, " layer {}", layer);
+        }
+
+        if attachment.width == 0 || attachment.height == 0 {
+            let _ = write!(explanation, " (zero size)");
+        } else if attachment.width != width || attachment.height != height {
+            let _ = write!(explanation, " (size differs from {}x{})", width, height);
+        }
+        if attachment.samples != samples {
+            let _ = write!(explanation, " (samples differ from {})", samples);
+        }
+    }
+
+    for draw_buffer in draw_buffers.iter() {
+        if !attachments.iter().any(|attachment| attachment.attachment == *draw_buffer) {
+            let _ = write!(explanation, "\n  draw buffer {:?} has no attachment", draw_buffer);
+        }
+    }
+
+    explanation
+}
+
+#[inline]
+fn texture_attachment(attachment: Attachment, texture: &Texture, level: usize, layer: Option<usize>) -> FramebufferAttachment {
+    FramebufferAttachment {
+        attachment: attachment,
+        target: texture.kind(),
+        id: texture.id(),
+        width: level_size(texture.width(), level),
+        height: level_size(texture.height(), level),
+        samples: texture.samples(),
+        format: texture.internal_format(),
+        level: level,
+        layer: layer,
+    }
+}
+
+#[inline]
+fn level_size(size: usize, level: usize) -> usize {
+    if size == 0 {
+        0
+    } else if (size >> level) > 1 {
+        size >> level
+    } else {
+        1
+    }
+}
+
+#[inline]
+fn record_attachment(attachments: &mut Vec<FramebufferAttachment>, attachment: FramebufferAttachment) {
+    attachments.retain(|a| a.attachment != attachment.attachment);
+    attachments.push(attachment);
+    attachments.sort_by(|a, b| a.attachment.cmp(&b.attachment));
+}
+
+#[inline]
+fn with_draw_buffer(draw_buffers: &[Attachment], attachment: Attachment) -> Option<Vec<Attachment>> {
+    match attachment {
+        Attachment::Color(_) if !draw_buffers.contains(&attachment) => {
+            let mut draw_buffers = draw_buffers.to_vec();
+            draw_buffers.push(attachment);
+            Some(draw_buffers)
+        },
+        _ => None,
+    }
+}
+
+#[inline]
+fn draw_buffers_to_gl(attachments: &[Attachment]) -> Vec<GLenum> {
+    let mut gl_enums = Vec::new();
+
+    for attachment in attachments {
+        if let &Attachment::Color(index) = attachment {
+            if index >= gl_enums.len() {
+                gl_enums.resize(index + 1, gl::NONE);
+            }
+            gl_enums[index] = attachment.to_gl();
+        }
+    }
+
+    gl_enums
+}
+
+#[inline]
+fn attachments_to_gl(attachments: &[Attachment]) -> Vec<GLenum> {
+    let mut gl_enums = Vec::with_capacity(attachments.len());
+    for attachment in attachments {
+        gl_enums.push(attachment.to_gl());
+    }
+    gl_enums
+}
+
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+
+    #[test]
+    fn test_draw_buffers_to_gl() {
+        assert!(draw_buffers_to_gl(&[]).is_empty());
+        assert_eq!(
+            draw_buffers_to_gl(&[Attachment::Color(0), Attachment::Color(1)]),
+            [gl::COLOR_ATTACHMENT0, gl::COLOR_ATTACHMENT1]
+        );
+        assert_eq!(
+            draw_buffers_to_gl(&[Attachment::Color(2), Attachment::Color(0)]),
+            [gl::COLOR_ATTACHMENT0, gl::NONE, gl::COLOR_ATTACHMENT2]
+        );
+        assert_eq!(
+            draw_buffers_to_gl(&[Attachment::Color(3)]),
+            [gl::NONE, gl::NONE, gl::NONE, gl::COLOR_ATTACHMENT3]
+        );
+    }
+
+    fn attachment(attachment: Attachment, target: GLenum, width: usize, height: usize, samples: usize) -> FramebufferAttachment {
+        FramebufferAttachment {
+            attachment: attachment,
+            target: target,
+            id: 1,
+            width: width,
+            height: height,
+            samples: samples,
+            format: InternalFormat::RGBA8,
+            level: 0,
+            layer: None,
+        }
+    }
+
+    #[test]
+    fn test_explain_status() {
+        let attachments = [
+            attachment(Attachment::Color(0), gl::TEXTURE_2D, 256, 128, 0),
+            attachment(Attachment::Color(1), gl::RENDERBUFFER, 128, 128, 4),
+            attachment(Attachment::Depth, gl::RENDERBUFFER, 0, 0, 0),
+        ];
+        let explanation = explain_status(
+            FramebufferStatus::IncompleteMultisample, &attachments, &[Attachment::Color(0), Attachment::Color(2)]
+        );
+        let lines: Vec<&str> = explanation.lines().collect();
+
+        assert_eq!(lines.len(), 5);
+        assert_eq!(lines[0], FramebufferStatus::IncompleteMultisample.description());
+        assert_eq!(lines[1], "  Color(0): texture 1 RGBA8 256x128 samples 0");
+        assert_eq!(lines[2], "  Color(1): renderbuffer 1 RGBA8 128x128 samples 4 (size differs from 256x128) (samples differ from 0)");
+        assert_eq!(lines[3], "  Depth: renderbuffer 1 RGBA8 0x0 samples 0 (zero size)");
+        assert_eq!(lines[4], "  draw buffer Color(2) has no attachment");
+    }
+
+    #[test]
+    fn test_explain_status_empty() {
+        let explanation = explain_status(FramebufferStatus::IncompleteMissingAttachment, &[], &[]);
+        assert_eq!(explanation, FramebufferStatus::IncompleteMissingAttachment.description());
+    }
+
+    fn no_extensions(_: &str) -> bool { false }
+
+    #[test]
+    fn test_check_descriptor() {
+        let color = AttachmentDescriptor::new(Attachment::Color(0), InternalFormat::RGBA8);
+        assert!(check_descriptor(false, 3, no_extensions, 8, true, &color).is_ok());
+        assert!(check_descriptor(true, 3, no_extensions, 4, true, &color).is_ok());
+        assert!(check_descriptor(true, 2, no_extensions, 1, true, &color).is_ok());
+
+        let color = AttachmentDescriptor::new(Attachment::Color(4), InternalFormat::RGBA8);
+        assert!(check_descriptor(true, 3, no_extensions, 4, true, &color).is_err());
+
+        let red = AttachmentDescriptor::new(Attachment::Color(0), InternalFormat::Red);
+        assert!(check_descriptor(true, 3, no_extensions, 4, true, &red).is_ok());
+
+        let float = AttachmentDescriptor::new(Attachment::Color(0), InternalFormat::RGBA16F);
+        assert!(check_descriptor(true, 2, no_extensions, 1, true, &float).is_err());
+
+        let depth = AttachmentDescriptor::new(Attachment::Depth, InternalFormat::DepthComponent16);
+        assert!(check_descriptor(true, 2, no_extensions, 1, true, &depth).is_err());
+        assert!(check_descriptor(true, 2, |name| name == "GL_OES_depth_texture", 1, true, &depth).is_ok());
+        assert!(check_descriptor(true, 3, no_extensions, 4, true, &depth).is_ok());
+
+        let depth_stencil = AttachmentDescriptor::new(Attachment::DepthStencil, InternalFormat::Depth32FStencil8);
+        assert!(check_descriptor(false, 3, no_extensions, 8, true, &depth_stencil).is_ok());
+
+        let mismatched = AttachmentDescriptor::new(Attachment::Depth, InternalFormat::RGBA8);
+        assert!(check_descriptor(false, 3, no_extensions, 8, true, &mismatched).is_err());
+        let mismatched = AttachmentDescriptor::new(Attachment::Color(0), InternalFormat::DepthComponent24);
+        assert!(check_descriptor(false, 3, no_extensions, 8, true, &mismatched).is_err());
+
+        let mut renderbuffer = AttachmentDescriptor::new(Attachment::Color(0), InternalFormat::RGBA);
+        renderbuffer.set_renderbuffer(true);
+        assert!(check_descriptor(false, 3, no_extensions, 8, true, &renderbuffer).is_ok());
+        assert!(check_descriptor(true, 3, no_extensions, 4, true, &renderbuffer).is_err());
+
+        let mut multisample = AttachmentDescriptor::new(Attachment::Color(0), InternalFormat::RGBA);
+        multisample.set_samples(4);
+        assert!(check_descriptor(false, 3, no_extensions, 8, true, &multisample).is_err());
+
+        let mut multisample = AttachmentDescriptor::new(Attachment::Color(0), InternalFormat::RGBA8);
+        multisample.set_samples(4);
+        assert!(check_descriptor(false, 3, no_extensions, 8, true, &multisample).is_ok());
+        assert!(check_descriptor(true, 3, no_extensions, 4, false, &multisample).is_err());
+    }
+
+    #[test]
+    fn test_resize_attachments() {
+        let mut attachments = Vec::new();
+        let mut draw_buffers = Vec::new();
+
+        for &(attachment, width) in [(Attachment::Depth, 64), (Attachment::Color(1), 64), (Attachment::Color(0), 64)].iter() {
+            record_attachment(&mut attachments, self::attachment(attachment, gl::TEXTURE_2D, width, width, 0));
+            if let Some(new_draw_buffers) = with_draw_buffer(&draw_buffers, attachment) {
+                draw_buffers = new_draw_buffers;
+            }
+        }
+        assert_eq!(draw_buffers, [Attachment::Color(1), Attachment::Color(0)]);
+
+        for &attachment in [Attachment::Color(0), Attachment::Color(1), Attachment::Depth].iter() {
+            record_attachment(&mut attachments, self::attachment(attachment, gl::TEXTURE_2D, 128, 128, 0));
+            assert_eq!(with_draw_buffer(&draw_buffers, attachment), None);
+        }
+
+        assert_eq!(attachments.len(), 3);
+        assert_eq!(attachments[0].attachment(), Attachment::Color(0));
+        assert_eq!(attachments[1].attachment(), Attachment::Color(1));
+        assert_eq!(attachments[2].attachment(), Attachment::Depth);
+        assert!(attachments.iter().all(|attachment| attachment.width() == 128 && attachment.height() == 128));
+        assert_eq!(draw_buffers, [Attachment::Color(1), Attachment::Color(0)]);
     }
 }
@@ -0,0 +1,307 @@
+use alloc::vec::Vec;
+
+use enums::CompressedFormat;
+use error::LoadError;
+use reader::{checked_offset, read_slice, read_u32, read_u64};
+use texture::{mip_levels, CompressedImage};
+
+
+static KTX1_IDENTIFIER: [u8; 12] = [0xAB, 0x4B, 0x54, 0x58, 0x20, 0x31, 0x31, 0xBB, 0x0D, 0x0A, 0x1A, 0x0A];
+static KTX2_IDENTIFIER: [u8; 12] = [0xAB, 0x4B, 0x54, 0x58, 0x20, 0x32, 0x30, 0xBB, 0x0D, 0x0A, 0x1A, 0x0A];
+
+static KTX1_ENDIANNESS: u32 = 0x04030201;
+static KTX1_HEADER_SIZE: usize = 64;
+static KTX2_HEADER_SIZE: usize = 80;
+static KTX2_LEVEL_INDEX_SIZE: usize = 24;
+
+
+#[inline]
+pub fn parse(bytes: &[u8]) -> Result<CompressedImage, LoadError> {
+    if bytes.len() < 12 {
+        Err(LoadError::Truncated)
+    } else if bytes[..12] == KTX1_IDENTIFIER {
+        parse_ktx1(bytes)
+    } else if bytes[..12] == KTX2_IDENTIFIER {
+        parse_ktx2(bytes)
+    } else {
+        Err(LoadError::InvalidIdentifier)
+    }
+}
+
+#[inline]
+fn parse_ktx1(bytes: &[u8]) -> Result<CompressedImage, LoadError> {
+    if bytes.len() < KTX1_HEADER_SIZE {
+        return Err(LoadError::Truncated);
+    }
+
+    let swap = match read_u32(bytes, 12, false)? {
+        endianness if endianness == KTX1_ENDIANNESS => false,
+        endianness if endianness.swap_bytes() == KTX1_ENDIANNESS => true,
+        _ => return Err(LoadError::InvalidIdentifier),
+    };
+
+    let gl_type = read_u32(bytes, 16, swap)?;
+    let gl_internal_format = read_u32(bytes, 28, swap)?;
+    let width = read_u32(bytes, 36, swap)? as usize;
+    let height = read_u32(bytes, 40, swap)? as usize;
+    let depth = read_u32(bytes, 44, swap)?;
+    let array_elements = read_u32(bytes, 48, swap)?;
+    let faces = read_u32(bytes, 52, swap)?;
+    let level_count = read_u32(bytes, 56, swap)? as usize;
+    let key_value_bytes = read_u32(bytes, 60, swap)? as usize;
+
+    if gl_type != 0 {
+        return Err(LoadError::UnsupportedFormat(gl_internal_format));
+    }
+    let format = match CompressedFormat::from_gl(gl_internal_format) {
+        Some(format) => format,
+        None => return Err(LoadError::UnsupportedFormat(gl_internal_format)),
+    };
+    if height == 0 || depth > 1 || array_elements > 1 || faces != 1 {
+        return Err(LoadError::UnsupportedLayout);
+    }
+
+    let level_count = if level_count == 0 {1} else {level_count};
+    if level_count > mip_levels(width, height) {
+        return Err(LoadError::UnsupportedLayout);
+    }
+
+    let mut levels = Vec::with_capacity(level_count);
+    let mut offset = checked_offset(KTX1_HEADER_SIZE, key_value_bytes)?;
+
+    for _ in 0..level_count {
+        let image_size = read_u32(bytes, offset, swap)? as usize;
+        offset = checked_offset(offset, 4)?;
+        levels.push(read_slice(bytes, offset, image_size)?);
+        offset = checked_offset(offset, image_size)?;
+        offset = checked_offset(offset, (4 - image_size % 4) % 4)?;
+    }
+
+    Ok(CompressedImage::new(width, height, format, levels))
+}
+
+#[inline]
+fn parse_ktx2(bytes: &[u8]) -> Result<CompressedImage, LoadError> {
+    if bytes.len() < KTX2_HEADER_SIZE {
+        return Err(LoadError::Truncated);
+    }
+
+    let vk_format = read_u32(bytes, 12, false)?;
+    let width = read_u32(bytes, 20, false)? as usize;
+    let height = read_u32(bytes, 24, false)? as usize;
+    let depth = read_u32(bytes, 28, false)?;
+    let layers = read_u32(bytes, 32, false)?;
+    let faces = read_u32(bytes, 36, false)?;
+    let level_count = read_u32(bytes, 40, false)? as usize;
+    let supercompression = read_u32(bytes, 44, false)?;
+
+    let format = match vk_format_to_compressed(vk_format) {
+        Some(format) => format,
+        None => return Err(LoadError::UnsupportedFormat(vk_format)),
+    };
+    if supercompression != 0 {
+        return Err(LoadError::Supercompressed(supercompression));
+    }
+    if height == 0 || depth > 1 || layers > 1 || faces != 1 {
+        return Err(LoadError::UnsupportedLayout);
+    }
+
+    let level_count = if level_count == 0 {1} else {level_count};
+    if level_count > mip_levels(width, height) {
+        return Err(LoadError::UnsupportedLayout);
+    }
+
+    let mut levels = Vec::with_capacity(level_count);
+
+    for level in 0..level_count {
+        let index = KTX2_HEADER_SIZE + level * KTX2_LEVEL_INDEX_SIZE;
+        let offset = read_u64(bytes, index)?;
+        let length = read_u64(bytes, index + 8)?;
+        levels.push(read_slice(bytes, offset, length)?);
+    }
+
+    Ok(CompressedImage::new(width, height, format, levels))
+}
+
+#[inline]
+fn vk_format_to_compressed(vk_format: u32) -> Option<CompressedFormat> {
+    match vk_format {
+        131 => Some(CompressedFormat::RgbS3tcDxt1),
+        133 => Some(CompressedFormat::RgbaS3tcDxt1),
+        135 => Some(CompressedFormat::RgbaS3tcDxt3),
+        137 => Some(CompressedFormat::RgbaS3tcDxt5),
+
+        139 => Some(CompressedFormat::RedRgtc1),
+        140 => Some(CompressedFormat::SignedRedRgtc1),
+        141 => Some(CompressedFormat::RgRgtc2),
+        142 => Some(CompressedFormat::SignedRgRgtc2),
+
+        143 => Some(CompressedFormat::RgbBptcUnsignedFloat),
+        144 => Some(CompressedFormat::RgbBptcSignedFloat),
+        145 => Some(CompressedFormat::RgbaBptcUnorm),
+        146 => Some(CompressedFormat::SrgbAlphaBptcUnorm),
+
+        147 => Some(CompressedFormat::Rgb8Etc2),
+        148 => Some(CompressedFormat::Srgb8Etc2),
+        149 => Some(CompressedFormat::Rgb8PunchthroughAlpha1Etc2),
+        151 => Some(CompressedFormat::Rgba8Etc2Eac),
+        152 => Some(CompressedFormat::Srgb8Alpha8Etc2Eac),
+        153 => Some(CompressedFormat::R11Eac),
+        154 => Some(CompressedFormat::SignedR11Eac),
+        155 => Some(CompressedFormat::Rg11Eac),
+        156 => Some(CompressedFormat::SignedRg11Eac),
+
+        157 => Some(CompressedFormat::RgbaAstc4x4),
+        161 => Some(CompressedFormat::RgbaAstc5x5),
+        165 => Some(CompressedFormat::RgbaAstc6x6),
+        171 => Some(CompressedFormat::RgbaAstc8x8),
+        179 => Some(CompressedFormat::RgbaAstc10x10),
+        183 => Some(CompressedFormat::RgbaAstc12x12),
+
+        _ => None,
+    }
+}
+
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use reader::write_u32;
+
+
+    fn ktx1_dxt1() -> [u8; 88] {
+        let mut bytes = [0u8; 88];
+        bytes[..12].copy_from_slice(&KTX1_IDENTIFIER);
+        write_u32(&mut bytes, 12, KTX1_ENDIANNESS);
+        write_u32(&mut bytes, 28, 0x83F1);
+        write_u32(&mut bytes, 36, 4);
+        write_u32(&mut bytes, 40, 4);
+        write_u32(&mut bytes, 52, 1);
+        write_u32(&mut bytes, 56, 2);
+        write_u32(&mut bytes, 64, 8);
+        bytes[68..76].copy_from_slice(&[1, 2, 3, 4, 5, 6, 7, 8]);
+        write_u32(&mut bytes, 76, 8);
+        bytes[80..88].copy_from_slice(&[9, 10, 11, 12, 13, 14, 15, 16]);
+        bytes
+    }
+
+    fn ktx2_bc7() -> [u8; 120] {
+        let mut bytes = [0u8; 120];
+        bytes[..12].copy_from_slice(&KTX2_IDENTIFIER);
+        write_u32(&mut bytes, 12, 145);
+        write_u32(&mut bytes, 20, 4);
+        write_u32(&mut bytes, 24, 4);
+        write_u32(&mut bytes, 36, 1);
+        write_u32(&mut bytes, 40, 1);
+        write_u32(&mut bytes, 80, 104);
+        write_u32(&mut bytes, 88, 16);
+        bytes
+    }
+
+    #[test]
+    fn test_parse_ktx1() {
+        let bytes = ktx1_dxt1();
+        let image = parse(&bytes).unwrap();
+
+        assert_eq!(image.width(), 4);
+        assert_eq!(image.height(), 4);
+        assert_eq!(image.format(), CompressedFormat::RgbaS3tcDxt1);
+        assert_eq!(image.levels().len(), 2);
+        assert_eq!(image.levels()[0], &[1, 2, 3, 4, 5, 6, 7, 8]);
+        assert_eq!(image.levels()[1], &[9, 10, 11, 12, 13, 14, 15, 16]);
+    }
+
+    #[test]
+    fn test_parse_ktx1_swapped() {
+        let mut bytes = ktx1_dxt1();
+        for offset in [12, 16, 20, 24, 28, 32, 36, 40, 44, 48, 52, 56, 60, 64, 76].iter() {
+            bytes[*offset..*offset + 4].reverse();
+        }
+        let image = parse(&bytes).unwrap();
+
+        assert_eq!(image.width(), 4);
+        assert_eq!(image.format(), CompressedFormat::RgbaS3tcDxt1);
+        assert_eq!(image.levels().len(), 2);
+    }
+
+    #[test]
+    fn test_parse_ktx1_errors() {
+        let bytes = ktx1_dxt1();
+        assert_eq!(parse(&bytes[..8]).err(), Some(LoadError::Truncated));
+        assert_eq!(parse(&bytes[..40]).err(), Some(LoadError::Truncated));
+        assert_eq!(parse(&bytes[..84]).err(), Some(LoadError::Truncated));
+
+        let mut bytes = ktx1_dxt1();
+        bytes[5] = 0x32;
+        assert_eq!(parse(&bytes).err(), Some(LoadError::InvalidIdentifier));
+
+        let mut bytes = ktx1_dxt1();
+        write_u32(&mut bytes, 52, 6);
+        assert_eq!(parse(&bytes).err(), Some(LoadError::UnsupportedLayout));
+
+        let mut bytes = ktx1_dxt1();
+        write_u32(&mut bytes, 16, 0x1401);
+        assert_eq!(parse(&bytes).err(), Some(LoadError::UnsupportedFormat(0x83F1)));
+    }
+
+    #[test]
+    fn test_parse_ktx2() {
+        let bytes = ktx2_bc7();
+        let image = parse(&bytes).unwrap();
+
+        assert_eq!(image.width(), 4);
+        assert_eq!(image.height(), 4);
+        assert_eq!(image.format(), CompressedFormat::RgbaBptcUnorm);
+        assert_eq!(image.levels().len(), 1);
+        assert_eq!(image.levels()[0].len(), 16);
+
+        let mut bytes = ktx2_bc7();
+        write_u32(&mut bytes, 44, 1);
+        assert_eq!(parse(&bytes).err(), Some(LoadError::Supercompressed(1)));
+
+        let mut bytes = ktx2_bc7();
+        write_u32(&mut bytes, 12, 37);
+        assert_eq!(parse(&bytes).err(), Some(LoadError::UnsupportedFormat(37)));
+
+        let mut bytes = ktx2_bc7();
+        write_u32(&mut bytes, 88, 17);
+        assert_eq!(parse(&bytes).err(), Some(LoadError::Truncated));
+    }
+
+    #[test]
+    fn test_parse_ktx1_malformed() {
+        let mut bytes = ktx1_dxt1();
+        write_u32(&mut bytes, 56, 0xFFFFFFFF);
+        assert_eq!(parse(&bytes).err(), Some(LoadError::UnsupportedLayout));
+
+        let mut bytes = ktx1_dxt1();
+        write_u32(&mut bytes, 60, 0xFFFFFFFC);
+        assert_eq!(parse(&bytes).err(), Some(LoadError::Truncated));
+
+        let mut bytes = ktx1_dxt1();
+        write_u32(&mut bytes, 64, 0xFFFFFFFF);
+        assert_eq!(parse(&bytes).err(), Some(LoadError::Truncated));
+
+        let mut bytes = ktx1_dxt1();
+        write_u32(&mut bytes, 36, 0xFFFFFFFF);
+        write_u32(&mut bytes, 40, 0xFFFFFFFF);
+        assert_eq!(parse(&bytes).map(|image| image.width()), Ok(0xFFFFFFFF));
+    }
+
+    #[test]
+    fn test_parse_ktx2_malformed() {
+        let mut bytes = ktx2_bc7();
+        write_u32(&mut bytes, 40, 0xFFFFFFFF);
+        assert_eq!(parse(&bytes).err(), Some(LoadError::UnsupportedLayout));
+
+        let mut bytes = ktx2_bc7();
+        write_u32(&mut bytes, 80, 0xFFFFFFFF);
+        write_u32(&mut bytes, 84, 0xFFFFFFFF);
+        assert_eq!(parse(&bytes).err(), Some(LoadError::Truncated));
+
+        let mut bytes = ktx2_bc7();
+        write_u32(&mut bytes, 88, 0xFFFFFFFF);
+        write_u32(&mut bytes, 92, 0xFFFFFFFF);
+        assert_eq!(parse(&bytes).err(), Some(LoadError::Truncated));
+    }
+}
@@ -3,29 +3,50 @@
 
 
 extern crate alloc;
+#[cfg(feature = "std")]
+extern crate std;
 
 extern crate regex;
 extern crate gl;
 
 
+#[macro_use]
+mod error;
+
 mod attribute;
 mod buffer;
 mod context;
+mod dds;
+mod debug;
 mod enums;
 mod framebuffer;
+mod ktx;
 mod program;
+mod reader;
 mod renderbuffer;
+mod render_pass;
+mod sampler;
+mod render_state;
 mod texture;
 mod uniform;
 mod vertex_array;
+#[cfg(debug_assertions)]
+mod verify;
 
 pub use attribute::*;
 pub use buffer::Buffer;
-pub use context::Context;
+pub use context::{Context, ContextState, StateGuard, DebugGroup};
+pub use debug::{DebugCallback, DebugMessage};
 pub use enums::*;
-pub use framebuffer::Framebuffer;
+pub use error::{GlError, GlErrorHandler, LoadError, FramebufferError, set_global_error_handler, global_error_handler_set};
+pub use framebuffer::{Framebuffer, FramebufferAttachment, AttachmentDescriptor};
 pub use program::{Program, link_program, compile_shader};
 pub use renderbuffer::Renderbuffer;
-pub use texture::Texture;
+pub use render_pass::{RenderPass, ColorTarget, DepthTarget};
+pub use sampler::Sampler;
+pub use render_state::{RenderState, RenderStateDiff, Stencil};
+pub use texture::{Texture, CompressedImage, ImageOptions, TextureRegion};
 pub use uniform::*;
 pub use vertex_array::VertexArray;
+#[cfg(debug_assertions)]
+pub use verify::StateMismatch;
@@ -17,6 +17,7 @@ use uniform::{new_uniform, Uniform};
 use attribute::{new_attribute, Attribute};
 use buffer::Buffer;
 use context::Context;
+use debug;
 
 
 pub struct Program {
@@ -30,6 +31,7 @@ impl Drop for Program {
     fn drop(&mut self) {
         if self.id != 0 {
             unsafe { gl::DeleteProgram(self.id); }
+            gl_check!("Program::drop");
         }
     }
 }
@@ -48,6 +50,12 @@ impl Program {
     #[inline(always)]
     pub fn id(&self) -> GLuint { self.id }
 
+    #[inline]
+    pub fn set_label(&self, context: &Context, label: &str) -> &Self {
+        debug::object_label(context, gl::PROGRAM, self.id, label);
+        self
+    }
+
     #[inline(always)]
     pub fn has_uniform(&self, name: &str) -> bool {self.uniforms.contains_key(&String::from(name))}
     #[inline(always)]
@@ -110,12 +118,14 @@ impl Program {
                 uniforms.clear();
                 attributes.clear();
                 unsafe { gl::DeleteProgram(self.id); }
+                gl_check!("Program::set_program_id");
             }
 
             self.id = id;
             parse_uniforms(id, uniforms);
             parse_attributes(id, attributes);
             unsafe { gl::UseProgram(0) };
+            gl_check!("Program::set_program_id");
         }
         self
     }
@@ -229,6 +239,7 @@ pub fn link_program(vertex_shader: GLuint, fragment_shader: GLuint) -> GLuint {
         gl::ValidateProgram(program);
         gl::UseProgram(program);
     }
+    gl_check!("link_program");
     check_program_status(program)
 }
 #[inline]
@@ -258,6 +269,7 @@ pub fn compile_shader(source: &str, kind: GLenum) -> GLuint {
         gl::ShaderSource(shader, 1, &ptr, &len);
         gl::CompileShader(shader);
     }
+    gl_check!("compile_shader");
     check_shader_status(shader)
 }
 #[inline]
@@ -272,6 +284,7 @@ pub fn compile_shaders(sources: &[&str], kind: GLenum) -> GLuint {
             gl::CompileShader(shader);
         }
     }
+    gl_check!("compile_shaders");
     check_shader_status(shader)
 }
 #[inline]
@@ -0,0 +1,117 @@
+use enums::CompressedFormat;
+use error::LoadError;
+
+
+#[inline]
+pub fn checked_offset(offset: usize, length: usize) -> Result<usize, LoadError> {
+    match offset.checked_add(length) {
+        Some(end) => Ok(end),
+        None => Err(LoadError::Truncated),
+    }
+}
+
+#[inline]
+pub fn read_slice(bytes: &[u8], offset: usize, length: usize) -> Result<&[u8], LoadError> {
+    let end = checked_offset(offset, length)?;
+
+    if end <= bytes.len() {
+        Ok(&bytes[offset..end])
+    } else {
+        Err(LoadError::Truncated)
+    }
+}
+
+#[inline]
+pub fn read_u32(bytes: &[u8], offset: usize, swap: bool) -> Result<u32, LoadError> {
+    let slice = read_slice(bytes, offset, 4)?;
+    let value =
+        (slice[0] as u32) |
+        ((slice[1] as u32) << 8) |
+        ((slice[2] as u32) << 16) |
+        ((slice[3] as u32) << 24);
+    Ok(if swap {value.swap_bytes()} else {value})
+}
+
+#[inline]
+pub fn read_u64(bytes: &[u8], offset: usize) -> Result<usize, LoadError> {
+    let low = read_u32(bytes, offset, false)? as u64;
+    let high = read_u32(bytes, checked_offset(offset, 4)?, false)? as u64;
+    let value = low | (high << 32);
+
+    if value > (usize::max_value() as u64) {
+        Err(LoadError::Truncated)
+    } else {
+        Ok(value as usize)
+    }
+}
+
+#[inline]
+pub fn level_size(size: usize, level: usize) -> usize {
+    match size.checked_shr(level as u32) {
+        Some(size) if size > 1 => size,
+        _ => 1,
+    }
+}
+
+#[inline]
+pub fn compressed_size(format: CompressedFormat, width: usize, height: usize) -> Result<usize, LoadError> {
+    let (block_width, block_height) = format.block_size();
+    let blocks_x = checked_offset(width, block_width - 1)? / block_width;
+    let blocks_y = checked_offset(height, block_height - 1)? / block_height;
+
+    match blocks_x.checked_mul(blocks_y).and_then(|blocks| blocks.checked_mul(format.block_bytes())) {
+        Some(size) => Ok(size),
+        None => Err(LoadError::Truncated),
+    }
+}
+
+
+#[cfg(test)]
+pub fn write_u32(bytes: &mut [u8], offset: usize, value: u32) {
+    bytes[offset] = value as u8;
+    bytes[offset + 1] = (value >> 8) as u8;
+    bytes[offset + 2] = (value >> 16) as u8;
+    bytes[offset + 3] = (value >> 24) as u8;
+}
+
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+
+    #[test]
+    fn test_read_slice() {
+        let bytes = [1u8, 2, 3, 4, 5, 6, 7, 8];
+        assert_eq!(read_slice(&bytes, 2, 3), Ok(&bytes[2..5]));
+        assert_eq!(read_slice(&bytes, 6, 3), Err(LoadError::Truncated));
+        assert_eq!(read_slice(&bytes, usize::max_value(), 2), Err(LoadError::Truncated));
+    }
+
+    #[test]
+    fn test_read_u32() {
+        let bytes = [0x01u8, 0x02, 0x03, 0x04];
+        assert_eq!(read_u32(&bytes, 0, false), Ok(0x04030201));
+        assert_eq!(read_u32(&bytes, 0, true), Ok(0x01020304));
+        assert_eq!(read_u32(&bytes, 1, false), Err(LoadError::Truncated));
+    }
+
+    #[test]
+    fn test_level_size() {
+        assert_eq!(level_size(256, 3), 32);
+        assert_eq!(level_size(256, 12), 1);
+        assert_eq!(level_size(256, 64), 1);
+        assert_eq!(level_size(256, 1000), 1);
+    }
+
+    #[test]
+    fn test_compressed_size() {
+        assert_eq!(compressed_size(CompressedFormat::RgbaS3tcDxt1, 4, 4), Ok(8));
+        assert_eq!(compressed_size(CompressedFormat::RgbaS3tcDxt5, 5, 1), Ok(32));
+        assert_eq!(compressed_size(CompressedFormat::RgbaS3tcDxt5, usize::max_value(), 4), Err(LoadError::Truncated));
+        assert_eq!(
+            compressed_size(CompressedFormat::RgbaS3tcDxt5, usize::max_value() / 2, usize::max_value() / 2),
+            Err(LoadError::Truncated)
+        );
+    }
+}
@@ -0,0 +1,465 @@
+use alloc::vec::Vec;
+
+use gl;
+use gl::types::*;
+
+use context::Context;
+use framebuffer::Framebuffer;
+use render_state::Stencil;
+use enums::{Attachment, FilterMode, LoadAction, StoreAction};
+
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct ColorTarget {
+    attachment: Attachment,
+    load_action: LoadAction,
+    store_action: StoreAction,
+    clear_color: [f32; 4],
+}
+
+impl ColorTarget {
+    #[inline(always)]
+    pub fn attachment(&self) -> Attachment { self.attachment }
+    #[inline(always)]
+    pub fn load_action(&self) -> LoadAction { self.load_action }
+    #[inline(always)]
+    pub fn store_action(&self) -> StoreAction { self.store_action }
+    #[inline(always)]
+    pub fn clear_color(&self) -> &[f32; 4] { &self.clear_color }
+}
+
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct DepthTarget {
+    attachment: Attachment,
+    load_action: LoadAction,
+    store_action: StoreAction,
+    clear_depth: f64,
+    clear_stencil: isize,
+}
+
+impl DepthTarget {
+    #[inline(always)]
+    pub fn attachment(&self) -> Attachment { self.attachment }
+    #[inline(always)]
+    pub fn load_action(&self) -> LoadAction { self.load_action }
+    #[inline(always)]
+    pub fn store_action(&self) -> StoreAction { self.store_action }
+    #[inline(always)]
+    pub fn clear_depth(&self) -> f64 { self.clear_depth }
+    #[inline(always)]
+    pub fn clear_stencil(&self) -> isize { self.clear_stencil }
+
+    #[inline(always)]
+    fn has_depth(&self) -> bool { self.attachment != Attachment::Stencil }
+    #[inline(always)]
+    fn has_stencil(&self) -> bool { self.attachment != Attachment::Depth }
+
+    #[inline]
+    fn buffer_bits(&self) -> GLbitfield {
+        let mut bits: GLbitfield = 0;
+
+        if self.has_depth() {
+            bits = bits | gl::DEPTH_BUFFER_BIT;
+        }
+        if self.has_stencil() {
+            bits = bits | gl::STENCIL_BUFFER_BIT;
+        }
+
+        bits
+    }
+}
+
+
+pub struct RenderPass<'a> {
+    framebuffer: Option<&'a Framebuffer>,
+    resolve_target: Option<&'a Framebuffer>,
+    viewport: [usize; 4],
+    colors: Vec<ColorTarget>,
+    depth: Option<DepthTarget>,
+}
+
+impl<'a> RenderPass<'a> {
+    #[inline]
+    pub fn new(framebuffer: Option<&'a Framebuffer>, width: usize, height: usize) -> Self {
+        RenderPass {
+            framebuffer: framebuffer,
+            resolve_target: None,
+            viewport: [0, 0, width, height],
+            colors: Vec::new(),
+            depth: None,
+        }
+    }
+
+    #[inline(always)]
+    pub fn framebuffer(&self) -> Option<&'a Framebuffer> { self.framebuffer }
+    #[inline(always)]
+    pub fn resolve_target(&self) -> Option<&'a Framebuffer> { self.resolve_target }
+    #[inline(always)]
+    pub fn viewport(&self) -> &[usize; 4] { &self.viewport }
+    #[inline(always)]
+    pub fn colors(&self) -> &[ColorTarget] { &self.colors }
+    #[inline(always)]
+    pub fn depth(&self) -> Option<&DepthTarget> { self.depth.as_ref() }
+
+    #[inline(always)]
+    pub fn set_resolve_target(&mut self, resolve_target: Option<&'a Framebuffer>) -> &mut Self {
+        self.resolve_target = resolve_target;
+        self
+    }
+    #[inline(always)]
+    pub fn set_viewport(&mut self, x: usize, y: usize, width: usize, height: usize) -> &mut Self {
+        self.viewport = [x, y, width, height];
+        self
+    }
+
+    #[inline]
+    pub fn add_color(
+        &mut self, attachment: Attachment, load_action: LoadAction, store_action: StoreAction, clear_color: &[f32; 4]
+    ) -> &mut Self {
+        match attachment {
+            Attachment::Color(_) => (),
+            _ => panic!("RenderPass::add_color expected a color attachment, got {:?}", attachment),
+        }
+        self.check_resolve(store_action);
+
+        self.colors.retain(|color| color.attachment != attachment);
+        self.colors.push(ColorTarget {
+            attachment: attachment,
+            load_action: load_action,
+            store_action: store_action,
+            clear_color: *clear_color,
+        });
+        self
+    }
+
+    #[inline]
+    pub fn set_depth(
+        &mut self, attachment: Attachment, load_action: LoadAction, store_action: StoreAction,
+        clear_depth: f64, clear_stencil: isize
+    ) -> &mut Self {
+        match attachment {
+            Attachment::Color(_) => panic!("RenderPass::set_depth expected a depth or stencil attachment, got {:?}", attachment),
+            _ => (),
+        }
+        self.check_resolve(store_action);
+
+        self.depth = Some(DepthTarget {
+            attachment: attachment,
+            load_action: load_action,
+            store_action: store_action,
+            clear_depth: clear_depth,
+            clear_stencil: clear_stencil,
+        });
+        self
+    }
+
+    #[inline]
+    pub fn begin(&self, context: &mut Context) -> &Self {
+        match self.framebuffer {
+            Some(framebuffer) => { context.set_framebuffer(framebuffer, false); },
+            None => { context.remove_framebuffer(false); },
+        }
+
+        context.set_viewport(self.viewport[0], self.viewport[1], self.viewport[2], self.viewport[3]);
+
+        let has_clear = self.colors.iter().any(|color| color.load_action == LoadAction::Clear) ||
+            self.depth.map_or(false, |depth| depth.load_action == LoadAction::Clear);
+
+        let depth_write = context.depth_write();
+        let color_mask = *context.color_mask();
+        let stencil = *context.stencil();
+        let scissor = context.scissor();
+
+        if has_clear {
+            context
+                .set_depth_write(true)
+                .set_color_mask(&[true; 4])
+                .set_stencil(&Stencil::new(
+                    stencil.func(), stencil.reference(), stencil.read_mask(), !0,
+                    stencil.fail(), stencil.depth_fail(), stencil.pass()
+                ))
+                .set_scissor(None);
+        }
+
+        let mut bits: GLbitfield = 0;
+
+        for color in &self.colors {
+            match color.load_action {
+                LoadAction::Clear => if gl::ClearBufferfv::is_loaded() {
+                    let draw_buffer = self.draw_buffer_index(color.attachment);
+                    unsafe { gl::ClearBufferfv(gl::COLOR, draw_buffer as GLint, color.clear_color.as_ptr()); }
+                    gl_check!("RenderPass::begin");
+                } else {
+                    context.set_clear_color(&color.clear_color);
+                    bits = bits | gl::COLOR_BUFFER_BIT;
+                },
+                LoadAction::DontCare | LoadAction::Load => (),
+            }
+        }
+
+        if let Some(ref depth) = self.depth {
+            if depth.load_action == LoadAction::Clear {
+                if depth.has_depth() {
+                    context.set_clear_depth(depth.clear_depth);
+                }
+                if depth.has_stencil() {
+                    context.set_clear_stencil(depth.clear_stencil);
+                }
+                bits = bits | depth.buffer_bits();
+            }
+        }
+
+        self.invalidate(context, &dont_care_attachments(&self.colors, self.depth.as_ref()));
+        if bits != 0 {
+            context.clear_bits(bits);
+        }
+
+        if has_clear {
+            context
+                .set_depth_write(depth_write)
+                .set_color_mask(&color_mask)
+                .set_stencil(&stencil)
+                .set_scissor(scissor);
+        }
+
+        self
+    }
+
+    #[inline]
+    pub fn end(&self, context: &mut Context) -> &Self {
+        let (discard, resolve, mask) = store_attachments(&self.colors, self.depth.as_ref());
+
+        if let Some(framebuffer) = self.framebuffer {
+            for attachment in resolve {
+                framebuffer.resolve_attachment(context, self.resolve_target, attachment, &self.viewport);
+            }
+            if mask != 0 {
+                context.blit_framebuffer(
+                    Some(framebuffer), self.resolve_target, &self.viewport, &self.viewport, mask, FilterMode::None
+                );
+            }
+        }
+        self.invalidate(context, &discard);
+
+        self
+    }
+
+    #[inline]
+    fn invalidate(&self, context: &mut Context, attachments: &[Attachment]) {
+        match self.framebuffer {
+            Some(framebuffer) => { framebuffer.invalidate(context, attachments); },
+            None => if !attachments.is_empty() && context.has_invalidate_framebuffer() {
+                let gl_enums = default_framebuffer_attachments(attachments);
+
+                context.remove_framebuffer(false);
+                unsafe { gl::InvalidateFramebuffer(gl::FRAMEBUFFER, gl_enums.len() as GLsizei, gl_enums.as_ptr()); }
+                gl_check!("RenderPass::invalidate");
+            },
+        }
+    }
+
+    #[inline]
+    fn check_resolve(&self, store_action: StoreAction) {
+        if store_action == StoreAction::Resolve && self.framebuffer.is_none() {
+            panic!("RenderPass can not resolve the default framebuffer");
+        }
+    }
+
+    #[inline]
+    fn draw_buffer_index(&self, attachment: Attachment) -> usize {
+        match self.framebuffer {
+            Some(framebuffer) => match framebuffer.draw_buffer_index(attachment) {
+                Some(index) => index,
+                None => panic!("RenderPass color target {:?} is not a draw buffer of the framebuffer", attachment),
+            },
+            None => 0,
+        }
+    }
+}
+
+
+#[inline]
+fn dont_care_attachments(colors: &[ColorTarget], depth: Option<&DepthTarget>) -> Vec<Attachment> {
+    let mut attachments: Vec<Attachment> = colors.iter()
+        .filter(|color| color.load_action == LoadAction::DontCare)
+        .map(|color| color.attachment)
+        .collect();
+
+    if let Some(depth) = depth {
+        if depth.load_action == LoadAction::DontCare {
+            attachments.push(depth.attachment);
+        }
+    }
+
+    attachments
+}
+
+#[inline]
+fn store_attachments(colors: &[ColorTarget], depth: Option<&DepthTarget>) -> (Vec<Attachment>, Vec<Attachment>, GLbitfield) {
+    let mut discard = Vec::new();
+    let mut resolve = Vec::new();
+    let mut mask: GLbitfield = 0;
+
+    for color in colors {
+        match color.store_action {
+            StoreAction::Resolve => {
+                resolve.push(color.attachment);
+                discard.push(color.attachment);
+            },
+            StoreAction::Discard => discard.push(color.attachment),
+            StoreAction::Store => (),
+        }
+    }
+
+    if let Some(depth) = depth {
+        match depth.store_action {
+            StoreAction::Resolve => {
+                mask = mask | depth.buffer_bits();
+                discard.push(depth.attachment);
+            },
+            StoreAction::Discard => discard.push(depth.attachment),
+            StoreAction::Store => (),
+        }
+    }
+
+    (discard, resolve, mask)
+}
+
+#[inline]
+fn default_framebuffer_attachments(attachments: &[Attachment]) -> Vec<GLenum> {
+    let mut gl_enums = Vec::new();
+
+    for attachment in attachments {
+        let buffers: &[GLenum] = match attachment {
+            &Attachment::Color(_) => &[gl::COLOR],
+            &Attachment::Depth => &[gl::DEPTH],
+            &Attachment::Stencil => &[gl::STENCIL],
+            &Attachment::DepthStencil => &[gl::DEPTH, gl::STENCIL],
+        };
+        for buffer in buffers {
+            if !gl_enums.contains(buffer) {
+                gl_enums.push(*buffer);
+            }
+        }
+    }
+
+    gl_enums
+}
+
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+
+    fn color(index: usize, load_action: LoadAction, store_action: StoreAction) -> ColorTarget {
+        ColorTarget {
+            attachment: Attachment::Color(index),
+            load_action: load_action,
+            store_action: store_action,
+            clear_color: [0.0; 4],
+        }
+    }
+
+    fn depth(attachment: Attachment, load_action: LoadAction, store_action: StoreAction) -> DepthTarget {
+        DepthTarget {
+            attachment: attachment,
+            load_action: load_action,
+            store_action: store_action,
+            clear_depth: 1.0,
+            clear_stencil: 0,
+        }
+    }
+
+    #[test]
+    fn test_dont_care_attachments() {
+        let colors = [
+            color(0, LoadAction::DontCare, StoreAction::Store),
+            color(1, LoadAction::Clear, StoreAction::Store),
+            color(2, LoadAction::Load, StoreAction::Store),
+        ];
+        let depth = depth(Attachment::DepthStencil, LoadAction::DontCare, StoreAction::Store);
+
+        assert_eq!(dont_care_attachments(&colors, None), [Attachment::Color(0)]);
+        assert_eq!(dont_care_attachments(&colors, Some(&depth)), [Attachment::Color(0), Attachment::DepthStencil]);
+        assert!(dont_care_attachments(&[], None).is_empty());
+    }
+
+    #[test]
+    fn test_store_attachments() {
+        let colors = [
+            color(0, LoadAction::Load, StoreAction::Resolve),
+            color(1, LoadAction::Load, StoreAction::Discard),
+            color(2, LoadAction::Load, StoreAction::Store),
+        ];
+
+        let (discard, resolve, mask) = store_attachments(&colors, None);
+        assert_eq!(discard, [Attachment::Color(0), Attachment::Color(1)]);
+        assert_eq!(resolve, [Attachment::Color(0)]);
+        assert_eq!(mask, 0);
+
+        let depth_stencil = depth(Attachment::DepthStencil, LoadAction::Load, StoreAction::Resolve);
+        let (discard, resolve, mask) = store_attachments(&[], Some(&depth_stencil));
+        assert_eq!(discard, [Attachment::DepthStencil]);
+        assert!(resolve.is_empty());
+        assert_eq!(mask, gl::DEPTH_BUFFER_BIT | gl::STENCIL_BUFFER_BIT);
+
+        let stencil = depth(Attachment::Stencil, LoadAction::Load, StoreAction::Discard);
+        let (discard, resolve, mask) = store_attachments(&[], Some(&stencil));
+        assert_eq!(discard, [Attachment::Stencil]);
+        assert!(resolve.is_empty());
+        assert_eq!(mask, 0);
+    }
+
+    #[test]
+    fn test_default_framebuffer_attachments() {
+        assert!(default_framebuffer_attachments(&[]).is_empty());
+        assert_eq!(
+            default_framebuffer_attachments(&[Attachment::Color(0), Attachment::Color(1), Attachment::Depth]),
+            [gl::COLOR, gl::DEPTH]
+        );
+        assert_eq!(
+            default_framebuffer_attachments(&[Attachment::DepthStencil, Attachment::Stencil]),
+            [gl::DEPTH, gl::STENCIL]
+        );
+    }
+
+    #[test]
+    fn test_check_resolve() {
+        let render_pass = RenderPass::new(None, 64, 64);
+        render_pass.check_resolve(StoreAction::Store);
+        render_pass.check_resolve(StoreAction::Discard);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_check_resolve_default_framebuffer() {
+        RenderPass::new(None, 64, 64).check_resolve(StoreAction::Resolve);
+    }
+
+    #[test]
+    fn test_add_attachments() {
+        let mut render_pass = RenderPass::new(None, 64, 64);
+        render_pass
+            .add_color(Attachment::Color(0), LoadAction::Clear, StoreAction::Store, &[1.0; 4])
+            .add_color(Attachment::Color(0), LoadAction::Load, StoreAction::Discard, &[0.0; 4])
+            .set_depth(Attachment::Depth, LoadAction::DontCare, StoreAction::Discard, 1.0, 0);
+
+        assert_eq!(render_pass.colors().len(), 1);
+        assert_eq!(render_pass.colors()[0].load_action(), LoadAction::Load);
+        assert_eq!(render_pass.depth().map(|depth| depth.attachment()), Some(Attachment::Depth));
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_add_color_depth_attachment() {
+        RenderPass::new(None, 64, 64).add_color(Attachment::Depth, LoadAction::Load, StoreAction::Store, &[0.0; 4]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_set_depth_color_attachment() {
+        RenderPass::new(None, 64, 64).set_depth(Attachment::Color(0), LoadAction::Load, StoreAction::Store, 1.0, 0);
+    }
+}
@@ -0,0 +1,291 @@
+use gl::types::*;
+
+use enums::{Blending, CullFace, Depth, StencilOp};
+
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct Stencil {
+    func: Depth,
+    reference: GLint,
+    read_mask: GLuint,
+    write_mask: GLuint,
+    fail: StencilOp,
+    depth_fail: StencilOp,
+    pass: StencilOp,
+}
+
+impl Stencil {
+    #[inline(always)]
+    pub fn new(
+        func: Depth,
+        reference: GLint,
+        read_mask: GLuint,
+        write_mask: GLuint,
+        fail: StencilOp,
+        depth_fail: StencilOp,
+        pass: StencilOp,
+    ) -> Self {
+        Stencil {
+            func: func,
+            reference: reference,
+            read_mask: read_mask,
+            write_mask: write_mask,
+            fail: fail,
+            depth_fail: depth_fail,
+            pass: pass,
+        }
+    }
+
+    #[inline(always)]
+    pub fn none() -> Self {
+        Stencil::new(Depth::None, 0, !0, !0, StencilOp::Keep, StencilOp::Keep, StencilOp::Keep)
+    }
+
+    #[inline(always)]
+    pub fn func(&self) -> Depth { self.func }
+    #[inline(always)]
+    pub fn reference(&self) -> GLint { self.reference }
+    #[inline(always)]
+    pub fn read_mask(&self) -> GLuint { self.read_mask }
+    #[inline(always)]
+    pub fn write_mask(&self) -> GLuint { self.write_mask }
+    #[inline(always)]
+    pub fn fail(&self) -> StencilOp { self.fail }
+    #[inline(always)]
+    pub fn depth_fail(&self) -> StencilOp { self.depth_fail }
+    #[inline(always)]
+    pub fn pass(&self) -> StencilOp { self.pass }
+}
+
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct RenderStateDiff {
+    blending: bool,
+    cull_face: bool,
+    depth_func: bool,
+    depth_write: bool,
+    depth_range: bool,
+    line_width: bool,
+    stencil: bool,
+    scissor: bool,
+    color_mask: bool,
+}
+
+impl RenderStateDiff {
+    #[inline(always)]
+    pub fn blending(&self) -> bool { self.blending }
+    #[inline(always)]
+    pub fn cull_face(&self) -> bool { self.cull_face }
+    #[inline(always)]
+    pub fn depth_func(&self) -> bool { self.depth_func }
+    #[inline(always)]
+    pub fn depth_write(&self) -> bool { self.depth_write }
+    #[inline(always)]
+    pub fn depth_range(&self) -> bool { self.depth_range }
+    #[inline(always)]
+    pub fn line_width(&self) -> bool { self.line_width }
+    #[inline(always)]
+    pub fn stencil(&self) -> bool { self.stencil }
+    #[inline(always)]
+    pub fn scissor(&self) -> bool { self.scissor }
+    #[inline(always)]
+    pub fn color_mask(&self) -> bool { self.color_mask }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        !(
+            self.blending || self.cull_face || self.depth_func ||
+            self.depth_write || self.depth_range || self.line_width ||
+            self.stencil || self.scissor || self.color_mask
+        )
+    }
+}
+
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct RenderState {
+    blending: Blending,
+    cull_face: CullFace,
+    depth_func: Depth,
+
+    depth_write: bool,
+    depth_range_near: f64,
+    depth_range_far: f64,
+    line_width: f32,
+
+    stencil: Stencil,
+    scissor: Option<[usize; 4]>,
+    color_mask: [bool; 4],
+}
+
+impl RenderState {
+
+    #[inline]
+    pub fn new() -> Self {
+        RenderState {
+            blending: Blending::Default,
+            cull_face: CullFace::Back,
+            depth_func: Depth::LessThan,
+
+            depth_write: true,
+            depth_range_near: 0f64,
+            depth_range_far: 1f64,
+            line_width: 1f32,
+
+            stencil: Stencil::none(),
+            scissor: None,
+            color_mask: [true, true, true, true],
+        }
+    }
+
+    #[inline(always)]
+    pub fn blending(&self) -> Blending { self.blending }
+    #[inline(always)]
+    pub fn cull_face(&self) -> CullFace { self.cull_face }
+    #[inline(always)]
+    pub fn depth_func(&self) -> Depth { self.depth_func }
+
+    #[inline(always)]
+    pub fn depth_write(&self) -> bool { self.depth_write }
+    #[inline(always)]
+    pub fn depth_range_near(&self) -> f64 { self.depth_range_near }
+    #[inline(always)]
+    pub fn depth_range_far(&self) -> f64 { self.depth_range_far }
+    #[inline(always)]
+    pub fn line_width(&self) -> f32 { self.line_width }
+
+    #[inline(always)]
+    pub fn stencil(&self) -> &Stencil { &self.stencil }
+    #[inline(always)]
+    pub fn scissor(&self) -> Option<[usize; 4]> { self.scissor }
+    #[inline(always)]
+    pub fn color_mask(&self) -> &[bool; 4] { &self.color_mask }
+
+    #[inline]
+    pub fn diff(&self, cached: &RenderState) -> RenderStateDiff {
+        RenderStateDiff {
+            blending: self.blending != cached.blending,
+            cull_face: self.cull_face != cached.cull_face,
+            depth_func: self.depth_func != cached.depth_func,
+            depth_write: self.depth_write != cached.depth_write,
+            depth_range:
+                self.depth_range_near != cached.depth_range_near ||
+                self.depth_range_far != cached.depth_range_far,
+            line_width: self.line_width != cached.line_width,
+            stencil: self.stencil != cached.stencil,
+            scissor: self.scissor != cached.scissor,
+            color_mask: self.color_mask != cached.color_mask,
+        }
+    }
+
+    #[inline(always)]
+    pub fn set_blending(&mut self, blending: Blending) -> &mut Self {
+        self.blending = blending;
+        self
+    }
+    #[inline(always)]
+    pub fn set_cull_face(&mut self, cull_face: CullFace) -> &mut Self {
+        self.cull_face = cull_face;
+        self
+    }
+    #[inline(always)]
+    pub fn set_depth_func(&mut self, depth_func: Depth) -> &mut Self {
+        self.depth_func = depth_func;
+        self
+    }
+
+    #[inline(always)]
+    pub fn set_depth_write(&mut self, depth_write: bool) -> &mut Self {
+        self.depth_write = depth_write;
+        self
+    }
+    #[inline(always)]
+    pub fn set_depth_range(&mut self, near: f64, far: f64) -> &mut Self {
+        self.depth_range_near = near;
+        self.depth_range_far = far;
+        self
+    }
+    #[inline(always)]
+    pub fn set_line_width(&mut self, line_width: f32) -> &mut Self {
+        self.line_width = line_width;
+        self
+    }
+
+    #[inline(always)]
+    pub fn set_stencil(&mut self, stencil: Stencil) -> &mut Self {
+        self.stencil = stencil;
+        self
+    }
+    #[inline(always)]
+    pub fn set_scissor(&mut self, scissor: Option<[usize; 4]>) -> &mut Self {
+        self.scissor = scissor;
+        self
+    }
+    #[inline(always)]
+    pub fn set_color_mask(&mut self, color_mask: &[bool; 4]) -> &mut Self {
+        self.color_mask = *color_mask;
+        self
+    }
+}
+
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use enums::{Blending, CullFace, Depth, StencilOp};
+
+
+    #[test]
+    fn test_diff_equal_states() {
+        let cached = RenderState::new();
+        let state = RenderState::new();
+        assert!(state.diff(&cached).is_empty());
+    }
+
+    #[test]
+    fn test_diff_reports_changed_fields() {
+        let cached = RenderState::new();
+        let mut state = RenderState::new();
+        state
+            .set_blending(Blending::Additive)
+            .set_depth_range(0f64, 0.5f64)
+            .set_scissor(Some([0, 0, 16, 16]));
+
+        let diff = state.diff(&cached);
+        assert!(!diff.is_empty());
+        assert!(diff.blending());
+        assert!(diff.depth_range());
+        assert!(diff.scissor());
+        assert!(!diff.cull_face());
+        assert!(!diff.depth_func());
+        assert!(!diff.depth_write());
+        assert!(!diff.line_width());
+        assert!(!diff.stencil());
+        assert!(!diff.color_mask());
+    }
+
+    #[test]
+    fn test_diff_stencil_and_masks() {
+        let cached = RenderState::new();
+        let mut state = RenderState::new();
+        state
+            .set_cull_face(CullFace::Front)
+            .set_depth_func(Depth::Always)
+            .set_depth_write(false)
+            .set_stencil(Stencil::new(
+                Depth::Equal, 1, !0, !0, StencilOp::Keep, StencilOp::Keep, StencilOp::Replace
+            ))
+            .set_color_mask(&[true, true, true, false]);
+
+        let diff = state.diff(&cached);
+        assert!(diff.cull_face());
+        assert!(diff.depth_func());
+        assert!(diff.depth_write());
+        assert!(diff.stencil());
+        assert!(diff.color_mask());
+        assert!(!diff.blending());
+
+        assert!(cached.diff(&cached).is_empty());
+        assert!(state.diff(&state).is_empty());
+    }
+}
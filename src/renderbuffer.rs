@@ -4,11 +4,16 @@ use gl;
 use gl::types::*;
 
 use context::Context;
-use enums::{TextureFormat, Attachment};
+use debug;
+use enums::InternalFormat;
 
 
 pub struct Renderbuffer {
     id: GLuint,
+    width: usize,
+    height: usize,
+    samples: usize,
+    format: InternalFormat,
 }
 
 impl Drop for Renderbuffer {
@@ -16,6 +21,7 @@ impl Drop for Renderbuffer {
     fn drop(&mut self) {
         if self.id != 0 {
             unsafe { gl::DeleteRenderbuffers(1, &self.id); }
+            gl_check!("Renderbuffer::drop");
         }
     }
 }
@@ -27,23 +33,61 @@ impl Renderbuffer {
             id: {
                 let mut id = 0;
                 unsafe { gl::GenRenderbuffers(1, &mut id); }
+                gl_check!("Renderbuffer::new");
                 id
             },
+            width: 0,
+            height: 0,
+            samples: 0,
+            format: InternalFormat::RGBA,
         }
     }
     #[inline(always)]
     pub fn id(&self) -> GLuint { self.id }
 
+    #[inline(always)]
+    pub fn width(&self) -> usize { self.width }
+    #[inline(always)]
+    pub fn height(&self) -> usize { self.height }
+    #[inline(always)]
+    pub fn samples(&self) -> usize { self.samples }
+    #[inline(always)]
+    pub fn format(&self) -> InternalFormat { self.format }
+
     #[inline]
-    pub fn set(&self, _: &Context, format: TextureFormat, attachment: Attachment, width: usize, height: usize) {
-        let format = format.to_gl();
-        let attachment = attachment.to_gl();
+    pub fn set_label(&self, context: &Context, label: &str) -> &Self {
+        debug::object_label(context, gl::RENDERBUFFER, self.id, label);
+        self
+    }
+
+    #[inline]
+    pub fn set_storage(
+        &mut self, context: &mut Context, format: InternalFormat, width: usize, height: usize, samples: usize
+    ) -> &mut Self {
+        if context.is_es() && !format.is_sized() {
+            panic!("Renderbuffer::set_storage requires a sized format on {:?}, got {:?}", context.version(), format);
+        }
+
+        let samples = if samples > context.max_samples() {context.max_samples()} else {samples};
+
+        context.set_renderbuffer(self, false);
 
         unsafe {
-            gl::BindRenderbuffer(gl::RENDERBUFFER, self.id);
-            gl::RenderbufferStorage(gl::RENDERBUFFER, format, width as GLint, height as GLint);
-            gl::FramebufferRenderbuffer(gl::FRAMEBUFFER, attachment, gl::RENDERBUFFER, self.id);
-            gl::BindRenderbuffer(gl::RENDERBUFFER, 0);
+            if samples > 0 {
+                gl::RenderbufferStorageMultisample(
+                    gl::RENDERBUFFER, samples as GLsizei, format.to_gl(), width as GLsizei, height as GLsizei
+                );
+            } else {
+                gl::RenderbufferStorage(gl::RENDERBUFFER, format.to_gl(), width as GLsizei, height as GLsizei);
+            }
         }
+        gl_check!("Renderbuffer::set_storage");
+
+        self.width = width;
+        self.height = height;
+        self.samples = samples;
+        self.format = format;
+
+        self
     }
 }
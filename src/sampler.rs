@@ -0,0 +1,259 @@
+use core::ops::Drop;
+
+use gl;
+use gl::types::*;
+
+use context::Context;
+use debug;
+use enums::{Depth, TextureFilter, MipmapFilter, TextureWrap};
+
+
+pub const TEXTURE_MAX_ANISOTROPY: GLenum = 0x84FE;
+pub const MAX_TEXTURE_MAX_ANISOTROPY: GLenum = 0x84FF;
+
+
+pub struct Sampler {
+    id: GLuint,
+
+    min_filter: TextureFilter,
+    mag_filter: TextureFilter,
+    mipmap_filter: MipmapFilter,
+
+    wrap_s: TextureWrap,
+    wrap_t: TextureWrap,
+    wrap_r: TextureWrap,
+    border_color: [f32; 4],
+
+    min_lod: f32,
+    max_lod: f32,
+    lod_bias: f32,
+
+    anisotropy: f32,
+    compare: Depth,
+}
+
+impl Drop for Sampler {
+    #[inline]
+    fn drop(&mut self) {
+        if self.id != 0 {
+            unsafe { gl::DeleteSamplers(1, &self.id); }
+            gl_check!("Sampler::drop");
+        }
+    }
+}
+
+impl Sampler {
+
+    #[inline]
+    pub fn new(context: &Context) -> Self {
+        if !context.has_samplers() {
+            panic!("Sampler objects require OpenGL 3.3 or OpenGL ES 3.0, got {:?}", context.version());
+        }
+
+        Sampler {
+            id: {
+                let mut id = 0;
+                unsafe { gl::GenSamplers(1, &mut id); }
+                gl_check!("Sampler::new");
+                id
+            },
+
+            min_filter: TextureFilter::Nearest,
+            mag_filter: TextureFilter::Linear,
+            mipmap_filter: MipmapFilter::Linear,
+
+            wrap_s: TextureWrap::Repeat,
+            wrap_t: TextureWrap::Repeat,
+            wrap_r: TextureWrap::Repeat,
+            border_color: [0f32, 0f32, 0f32, 0f32],
+
+            min_lod: -1000f32,
+            max_lod: 1000f32,
+            lod_bias: 0f32,
+
+            anisotropy: 1f32,
+            compare: Depth::None,
+        }
+    }
+
+    #[inline(always)]
+    pub fn id(&self) -> GLuint { self.id }
+
+    #[inline]
+    pub fn set_label(&self, context: &Context, label: &str) -> &Self {
+        debug::object_label(context, gl::SAMPLER, self.id, label);
+        self
+    }
+
+    #[inline(always)]
+    pub fn min_filter(&self) -> TextureFilter { self.min_filter }
+    #[inline(always)]
+    pub fn mag_filter(&self) -> TextureFilter { self.mag_filter }
+    #[inline(always)]
+    pub fn mipmap_filter(&self) -> MipmapFilter { self.mipmap_filter }
+
+    #[inline(always)]
+    pub fn wrap_s(&self) -> TextureWrap { self.wrap_s }
+    #[inline(always)]
+    pub fn wrap_t(&self) -> TextureWrap { self.wrap_t }
+    #[inline(always)]
+    pub fn wrap_r(&self) -> TextureWrap { self.wrap_r }
+    #[inline(always)]
+    pub fn border_color(&self) -> &[f32; 4] { &self.border_color }
+
+    #[inline(always)]
+    pub fn min_lod(&self) -> f32 { self.min_lod }
+    #[inline(always)]
+    pub fn max_lod(&self) -> f32 { self.max_lod }
+    #[inline(always)]
+    pub fn lod_bias(&self) -> f32 { self.lod_bias }
+
+    #[inline(always)]
+    pub fn anisotropy(&self) -> f32 { self.anisotropy }
+    #[inline(always)]
+    pub fn compare(&self) -> Depth { self.compare }
+
+    #[inline]
+    pub fn set_filter(&mut self, min_filter: TextureFilter, mag_filter: TextureFilter, mipmap_filter: MipmapFilter) -> &mut Self {
+        unsafe {
+            gl::SamplerParameteri(self.id, gl::TEXTURE_MIN_FILTER, mipmap_filter.to_gl(min_filter) as GLint);
+            gl::SamplerParameteri(self.id, gl::TEXTURE_MAG_FILTER, mag_filter.to_gl() as GLint);
+        }
+        gl_check!("Sampler::set_filter");
+
+        self.min_filter = min_filter;
+        self.mag_filter = mag_filter;
+        self.mipmap_filter = mipmap_filter;
+
+        self
+    }
+
+    #[inline]
+    pub fn set_wrap(
+        &mut self, context: &Context, wrap_s: TextureWrap, wrap_t: TextureWrap, wrap_r: TextureWrap
+    ) -> &mut Self {
+        if !context.has_border_clamp() && [wrap_s, wrap_t, wrap_r].contains(&TextureWrap::ClampToBorder) {
+            panic!(
+                "{:?} requires OpenGL ES 3.2 or GL_EXT_texture_border_clamp, got {:?}",
+                TextureWrap::ClampToBorder, context.version()
+            );
+        }
+
+        unsafe {
+            gl::SamplerParameteri(self.id, gl::TEXTURE_WRAP_S, wrap_s.to_gl() as GLint);
+            gl::SamplerParameteri(self.id, gl::TEXTURE_WRAP_T, wrap_t.to_gl() as GLint);
+            gl::SamplerParameteri(self.id, gl::TEXTURE_WRAP_R, wrap_r.to_gl() as GLint);
+        }
+        gl_check!("Sampler::set_wrap");
+
+        self.wrap_s = wrap_s;
+        self.wrap_t = wrap_t;
+        self.wrap_r = wrap_r;
+
+        self
+    }
+
+    #[inline]
+    pub fn set_border_color(&mut self, context: &Context, border_color: &[f32; 4]) -> &mut Self {
+        if !context.has_border_clamp() {
+            panic!(
+                "Sampler::set_border_color requires OpenGL ES 3.2 or GL_EXT_texture_border_clamp, got {:?}",
+                context.version()
+            );
+        }
+
+        unsafe { gl::SamplerParameterfv(self.id, gl::TEXTURE_BORDER_COLOR, border_color.as_ptr()); }
+        gl_check!("Sampler::set_border_color");
+
+        self.border_color = *border_color;
+
+        self
+    }
+
+    #[inline]
+    pub fn set_lod(&mut self, min_lod: f32, max_lod: f32, lod_bias: f32) -> &mut Self {
+        unsafe {
+            gl::SamplerParameterf(self.id, gl::TEXTURE_MIN_LOD, min_lod);
+            gl::SamplerParameterf(self.id, gl::TEXTURE_MAX_LOD, max_lod);
+            gl::SamplerParameterf(self.id, gl::TEXTURE_LOD_BIAS, lod_bias);
+        }
+        gl_check!("Sampler::set_lod");
+
+        self.min_lod = min_lod;
+        self.max_lod = max_lod;
+        self.lod_bias = lod_bias;
+
+        self
+    }
+
+    #[inline]
+    pub fn set_anisotropy(&mut self, context: &Context, anisotropy: f32) -> &mut Self {
+        if let Some(anisotropy) = clamp_anisotropy(anisotropy, context.max_anisotropy() as f32) {
+            unsafe { gl::SamplerParameterf(self.id, TEXTURE_MAX_ANISOTROPY, anisotropy); }
+            gl_check!("Sampler::set_anisotropy");
+
+            self.anisotropy = anisotropy;
+        }
+
+        self
+    }
+
+    #[inline]
+    pub fn set_compare(&mut self, compare: Depth) -> &mut Self {
+        unsafe {
+            if compare == Depth::None {
+                gl::SamplerParameteri(self.id, gl::TEXTURE_COMPARE_MODE, gl::NONE as GLint);
+            } else {
+                gl::SamplerParameteri(self.id, gl::TEXTURE_COMPARE_MODE, gl::COMPARE_REF_TO_TEXTURE as GLint);
+                gl::SamplerParameteri(self.id, gl::TEXTURE_COMPARE_FUNC, compare.to_gl() as GLint);
+            }
+        }
+        gl_check!("Sampler::set_compare");
+
+        self.compare = compare;
+
+        self
+    }
+}
+
+#[inline]
+fn clamp_anisotropy(anisotropy: f32, max_anisotropy: f32) -> Option<f32> {
+    if max_anisotropy < 1f32 {
+        None
+    } else if anisotropy < 1f32 {
+        Some(1f32)
+    } else if anisotropy > max_anisotropy {
+        Some(max_anisotropy)
+    } else {
+        Some(anisotropy)
+    }
+}
+
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+
+    #[test]
+    fn test_clamp_anisotropy() {
+        assert_eq!(clamp_anisotropy(4f32, 16f32), Some(4f32));
+        assert_eq!(clamp_anisotropy(32f32, 16f32), Some(16f32));
+        assert_eq!(clamp_anisotropy(0.5f32, 16f32), Some(1f32));
+        assert_eq!(clamp_anisotropy(-2f32, 16f32), Some(1f32));
+        assert_eq!(clamp_anisotropy(16f32, 16f32), Some(16f32));
+    }
+
+    #[test]
+    fn test_clamp_anisotropy_unsupported() {
+        assert_eq!(clamp_anisotropy(4f32, 0f32), None);
+        assert_eq!(clamp_anisotropy(1f32, 0.5f32), None);
+    }
+
+    #[test]
+    fn test_min_filter() {
+        assert_eq!(MipmapFilter::None.to_gl(TextureFilter::Linear), gl::LINEAR);
+        assert_eq!(MipmapFilter::Nearest.to_gl(TextureFilter::Linear), gl::LINEAR_MIPMAP_NEAREST);
+        assert_eq!(MipmapFilter::Linear.to_gl(TextureFilter::Nearest), gl::NEAREST_MIPMAP_LINEAR);
+    }
+}
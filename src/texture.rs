@@ -1,3 +1,5 @@
+use alloc::vec::Vec;
+
 use core::ptr;
 use core::mem;
 use core::ops::Drop;
@@ -5,13 +7,161 @@ use core::ops::Drop;
 use gl;
 use gl::types::*;
 
+use buffer::Buffer;
 use context::Context;
-use enums::{TextureFormat, TextureWrap, TextureKind, FilterMode};
+use dds;
+use debug;
+use error::LoadError;
+use framebuffer::Framebuffer;
+use ktx;
+use enums::{BufferTarget, CompressedFormat, CubeFace, FramebufferStatus, TextureFormat, TextureWrap, TextureKind, FilterMode, InternalFormat};
 
 
 pub struct Texture {
     id: GLuint,
     kind: GLenum,
+
+    width: usize,
+    height: usize,
+    depth: usize,
+    levels: usize,
+    samples: usize,
+    base_level: usize,
+    max_level: usize,
+    immutable: bool,
+
+    internal_format: InternalFormat,
+    format: TextureFormat,
+    data_kind: TextureKind,
+    compressed_format: Option<CompressedFormat>,
+    wrap: TextureWrap,
+    filter: FilterMode,
+}
+
+
+pub struct CompressedImage<'a> {
+    width: usize,
+    height: usize,
+    format: CompressedFormat,
+    levels: Vec<&'a [u8]>,
+}
+
+impl<'a> CompressedImage<'a> {
+    #[inline(always)]
+    pub fn new(width: usize, height: usize, format: CompressedFormat, levels: Vec<&'a [u8]>) -> Self {
+        CompressedImage {
+            width: width,
+            height: height,
+            format: format,
+            levels: levels,
+        }
+    }
+
+    #[inline(always)]
+    pub fn width(&self) -> usize { self.width }
+    #[inline(always)]
+    pub fn height(&self) -> usize { self.height }
+    #[inline(always)]
+    pub fn format(&self) -> CompressedFormat { self.format }
+    #[inline(always)]
+    pub fn levels(&self) -> &[&'a [u8]] { &self.levels }
+}
+
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct TextureRegion {
+    x: usize,
+    y: usize,
+    z: usize,
+    width: usize,
+    height: usize,
+    depth: usize,
+}
+
+impl TextureRegion {
+    #[inline(always)]
+    pub fn new(x: usize, y: usize, width: usize, height: usize) -> Self {
+        TextureRegion::new3d(x, y, 0, width, height, 1)
+    }
+    #[inline(always)]
+    pub fn new3d(x: usize, y: usize, z: usize, width: usize, height: usize, depth: usize) -> Self {
+        TextureRegion {
+            x: x,
+            y: y,
+            z: z,
+            width: width,
+            height: height,
+            depth: depth,
+        }
+    }
+
+    #[inline(always)]
+    pub fn x(&self) -> usize { self.x }
+    #[inline(always)]
+    pub fn y(&self) -> usize { self.y }
+    #[inline(always)]
+    pub fn z(&self) -> usize { self.z }
+    #[inline(always)]
+    pub fn width(&self) -> usize { self.width }
+    #[inline(always)]
+    pub fn height(&self) -> usize { self.height }
+    #[inline(always)]
+    pub fn depth(&self) -> usize { self.depth }
+
+    #[inline(always)]
+    pub fn size(&self, format: TextureFormat, kind: TextureKind) -> usize {
+        self.width * self.height * self.depth * kind.pixel_size(format)
+    }
+}
+
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct ImageOptions {
+    format: TextureFormat,
+    kind: TextureKind,
+    wrap: TextureWrap,
+    filter: FilterMode,
+    generate_mipmap: bool,
+}
+
+impl ImageOptions {
+    #[inline(always)]
+    pub fn new(format: TextureFormat, kind: TextureKind) -> Self {
+        ImageOptions {
+            format: format,
+            kind: kind,
+            wrap: TextureWrap::Clamp,
+            filter: FilterMode::Linear,
+            generate_mipmap: false,
+        }
+    }
+
+    #[inline(always)]
+    pub fn format(&self) -> TextureFormat { self.format }
+    #[inline(always)]
+    pub fn kind(&self) -> TextureKind { self.kind }
+    #[inline(always)]
+    pub fn wrap(&self) -> TextureWrap { self.wrap }
+    #[inline(always)]
+    pub fn filter(&self) -> FilterMode { self.filter }
+    #[inline(always)]
+    pub fn generate_mipmap(&self) -> bool { self.generate_mipmap }
+
+    #[inline(always)]
+    pub fn set_wrap(&mut self, wrap: TextureWrap) -> &mut Self {
+        self.wrap = wrap;
+        self
+    }
+    #[inline(always)]
+    pub fn set_filter(&mut self, filter: FilterMode) -> &mut Self {
+        self.filter = filter;
+        self
+    }
+    #[inline(always)]
+    pub fn set_generate_mipmap(&mut self, generate_mipmap: bool) -> &mut Self {
+        self.generate_mipmap = generate_mipmap;
+        self
+    }
 }
 
 impl Drop for Texture {
@@ -19,6 +169,7 @@ impl Drop for Texture {
     fn drop(&mut self) {
         if self.id != 0 {
             unsafe { gl::DeleteTextures(1, &self.id); }
+            gl_check!("Texture::drop");
         }
     }
 }
@@ -28,6 +179,7 @@ macro_rules! texture_options {
         $context: ident,
         $width: ident,
         $height: ident,
+        $internal_format: ident,
         $format: ident,
         $kind: ident,
         $wrap: ident,
@@ -37,29 +189,34 @@ macro_rules! texture_options {
         $gl_major: ident,
         $gl_minor: ident,
 
-        $gl_pot: ident,
+        $gl_can_mipmap: ident,
+        $gl_internal_format: ident,
         $gl_format: ident,
         $gl_kind: ident,
         $gl_wrap: ident,
         $gl_mag_filter: ident,
         $gl_min_filter: ident
     ) => (
-        let $gl_pot = $width.is_power_of_two() && $height.is_power_of_two();
+        validate_format($context, $internal_format, $format, $kind);
+        check_wrap($context, $wrap);
 
         let $gl_major = $context.major();
         let $gl_minor = $context.minor();
 
+        let $gl_can_mipmap = $gl_major >= 3 || ($width.is_power_of_two() && $height.is_power_of_two());
+
         let $gl_mag_filter;
         let $gl_min_filter;
 
         if $filter == FilterMode::None {
             $gl_mag_filter = gl::NEAREST;
-            $gl_min_filter = if $gl_pot && $generate_mipmap {gl::LINEAR_MIPMAP_NEAREST} else {gl::NEAREST};
+            $gl_min_filter = if $gl_can_mipmap && $generate_mipmap {gl::LINEAR_MIPMAP_NEAREST} else {gl::NEAREST};
         } else {
             $gl_mag_filter = gl::LINEAR;
-            $gl_min_filter = if $gl_pot && $generate_mipmap {gl::LINEAR_MIPMAP_LINEAR} else {gl::LINEAR};
+            $gl_min_filter = if $gl_can_mipmap && $generate_mipmap {gl::LINEAR_MIPMAP_LINEAR} else {gl::LINEAR};
         }
 
+        let $gl_internal_format = gl_internal_format($context, $internal_format, $format);
         let $gl_format = $format.to_gl();
         let $gl_wrap = $wrap.to_gl() as GLint;
         let $gl_kind = $kind.to_gl();
@@ -69,22 +226,30 @@ macro_rules! texture_options {
 macro_rules! generate_mipmap {
     (
         $id: expr,
+        $target: expr,
         $generate_mipmap: ident,
         $gl_major: ident,
         $gl_minor: ident,
-        $gl_pot: ident
+        $gl_can_mipmap: ident
     ) => (
-        if $generate_mipmap && $gl_pot {
-            if $gl_major >= 4 && $gl_minor >= 5 {
+        if $generate_mipmap && $gl_can_mipmap {
+            if $gl_major > 4 || ($gl_major == 4 && $gl_minor >= 5) {
                 gl::GenerateTextureMipmap($id);
             } else {
-                gl::GenerateMipmap(gl::TEXTURE_2D);
+                gl::GenerateMipmap($target);
             }
         }
     )
 }
 
 
+static CUBE_FACES: [CubeFace; 6] = [
+    CubeFace::PositiveX, CubeFace::NegativeX,
+    CubeFace::PositiveY, CubeFace::NegativeY,
+    CubeFace::PositiveZ, CubeFace::NegativeZ,
+];
+
+
 impl Texture {
 
     #[inline(always)]
@@ -93,43 +258,460 @@ impl Texture {
             id: {
                 let mut id = 0;
                 unsafe { gl::GenTextures(1, &mut id); }
+                gl_check!("Texture::new");
                 id
             },
             kind: gl::TEXTURE_2D,
+
+            width: 0,
+            height: 0,
+            depth: 0,
+            levels: 0,
+            samples: 0,
+            base_level: 0,
+            max_level: 1000,
+            immutable: false,
+
+            internal_format: InternalFormat::RGBA,
+            format: TextureFormat::RGBA,
+            data_kind: TextureKind::UnsignedByte,
+            compressed_format: None,
+            wrap: TextureWrap::Repeat,
+            filter: FilterMode::Linear,
         }
     }
 
     #[inline(always)]
     pub fn id(&self) -> GLuint { self.id }
 
+    #[inline]
+    pub fn set_label(&self, context: &Context, label: &str) -> &Self {
+        debug::object_label(context, gl::TEXTURE, self.id, label);
+        self
+    }
+
     #[inline(always)]
     pub fn kind(&self) -> GLenum { self.kind }
 
+    #[inline(always)]
+    pub fn width(&self) -> usize { self.width }
+    #[inline(always)]
+    pub fn height(&self) -> usize { self.height }
+    #[inline(always)]
+    pub fn depth(&self) -> usize { self.depth }
+    #[inline(always)]
+    pub fn levels(&self) -> usize { self.levels }
+    #[inline(always)]
+    pub fn samples(&self) -> usize { self.samples }
+    #[inline(always)]
+    pub fn base_level(&self) -> usize { self.base_level }
+    #[inline(always)]
+    pub fn max_level(&self) -> usize { self.max_level }
+    #[inline(always)]
+    pub fn is_immutable(&self) -> bool { self.immutable }
+
+    #[inline(always)]
+    pub fn internal_format(&self) -> InternalFormat { self.internal_format }
+    #[inline(always)]
+    pub fn format(&self) -> TextureFormat { self.format }
+    #[inline(always)]
+    pub fn data_kind(&self) -> TextureKind { self.data_kind }
+    #[inline(always)]
+    pub fn compressed_format(&self) -> Option<CompressedFormat> { self.compressed_format }
+    #[inline(always)]
+    pub fn wrap(&self) -> TextureWrap { self.wrap }
+    #[inline(always)]
+    pub fn filter(&self) -> FilterMode { self.filter }
+
+    #[inline]
+    pub fn is_valid_format(
+        context: &Context,
+        internal_format: InternalFormat,
+        format: TextureFormat,
+        kind: TextureKind
+    ) -> bool {
+        valid_format(
+            context.is_es(), context.major(), |name| context.has_extenstion(name), internal_format, format, kind
+        )
+    }
+
+    #[inline]
+    pub fn is_supported_compressed_format(context: &Context, format: CompressedFormat) -> bool {
+        let major = context.major();
+        let minor = context.minor();
+        let is_es = context.is_es();
+
+        match format {
+            CompressedFormat::RgbS3tcDxt1 |
+            CompressedFormat::RgbaS3tcDxt1 => {
+                context.has_extenstion("GL_EXT_texture_compression_s3tc") ||
+                context.has_extenstion("GL_EXT_texture_compression_dxt1")
+            },
+            CompressedFormat::RgbaS3tcDxt3 |
+            CompressedFormat::RgbaS3tcDxt5 => {
+                context.has_extenstion("GL_EXT_texture_compression_s3tc") ||
+                context.has_extenstion("GL_ANGLE_texture_compression_dxt5")
+            },
+
+            CompressedFormat::RedRgtc1 |
+            CompressedFormat::SignedRedRgtc1 |
+            CompressedFormat::RgRgtc2 |
+            CompressedFormat::SignedRgRgtc2 => {
+                (!is_es && major >= 3) ||
+                context.has_extenstion("GL_ARB_texture_compression_rgtc") ||
+                context.has_extenstion("GL_EXT_texture_compression_rgtc")
+            },
+
+            CompressedFormat::RgbBptcSignedFloat |
+            CompressedFormat::RgbBptcUnsignedFloat |
+            CompressedFormat::RgbaBptcUnorm |
+            CompressedFormat::SrgbAlphaBptcUnorm => {
+                (!is_es && (major > 4 || (major == 4 && minor >= 2))) ||
+                context.has_extenstion("GL_ARB_texture_compression_bptc") ||
+                context.has_extenstion("GL_EXT_texture_compression_bptc")
+            },
+
+            CompressedFormat::Rgb8Etc2 |
+            CompressedFormat::Srgb8Etc2 |
+            CompressedFormat::Rgb8PunchthroughAlpha1Etc2 |
+            CompressedFormat::Rgba8Etc2Eac |
+            CompressedFormat::Srgb8Alpha8Etc2Eac |
+            CompressedFormat::R11Eac |
+            CompressedFormat::SignedR11Eac |
+            CompressedFormat::Rg11Eac |
+            CompressedFormat::SignedRg11Eac => {
+                (is_es && major >= 3) ||
+                (!is_es && (major > 4 || (major == 4 && minor >= 3))) ||
+                context.has_extenstion("GL_ARB_ES3_compatibility")
+            },
+
+            CompressedFormat::RgbaAstc4x4 |
+            CompressedFormat::RgbaAstc5x5 |
+            CompressedFormat::RgbaAstc6x6 |
+            CompressedFormat::RgbaAstc8x8 |
+            CompressedFormat::RgbaAstc10x10 |
+            CompressedFormat::RgbaAstc12x12 => {
+                context.has_extenstion("GL_KHR_texture_compression_astc_ldr") ||
+                context.has_extenstion("GL_OES_texture_compression_astc")
+            },
+        }
+    }
+
+    #[inline]
+    pub fn from_ktx(context: &Context, bytes: &[u8], wrap: TextureWrap, filter: FilterMode) -> Result<Self, LoadError> {
+        let image = ktx::parse(bytes)?;
+        Self::from_compressed_image(context, &image, wrap, filter)
+    }
+
+    #[inline]
+    pub fn from_dds(context: &Context, bytes: &[u8], wrap: TextureWrap, filter: FilterMode) -> Result<Self, LoadError> {
+        let image = dds::parse(bytes)?;
+        Self::from_compressed_image(context, &image, wrap, filter)
+    }
+
+    #[inline]
+    pub fn from_compressed_image(
+        context: &Context,
+        image: &CompressedImage,
+        wrap: TextureWrap,
+        filter: FilterMode
+    ) -> Result<Self, LoadError> {
+        if !Self::is_supported_compressed_format(context, image.format()) {
+            return Err(LoadError::UnsupportedByContext(image.format()));
+        }
+
+        let mut texture = Texture::new();
+        texture.set_compressed2d(context, image, wrap, filter);
+        Ok(texture)
+    }
+
+    #[inline]
+    pub fn set_compressed2d(
+        &mut self,
+        context: &Context,
+        image: &CompressedImage,
+        wrap: TextureWrap,
+        filter: FilterMode
+    ) -> &mut Self {
+        let (width, height, format, levels) = (image.width(), image.height(), image.format(), image.levels());
+
+        if !Self::is_supported_compressed_format(context, format) {
+            panic!("Compressed format {:?} is not supported by {:?}", format, context.version());
+        }
+        if levels.is_empty() {
+            panic!("Texture::set_compressed2d requires at least one mip level");
+        }
+
+        let gl_mag_filter;
+        let gl_min_filter;
+
+        if filter == FilterMode::None {
+            gl_mag_filter = gl::NEAREST;
+            gl_min_filter = if levels.len() > 1 {gl::LINEAR_MIPMAP_NEAREST} else {gl::NEAREST};
+        } else {
+            gl_mag_filter = gl::LINEAR;
+            gl_min_filter = if levels.len() > 1 {gl::LINEAR_MIPMAP_LINEAR} else {gl::LINEAR};
+        }
+
+        let gl_format = format.to_gl();
+        let gl_wrap = wrap.to_gl() as GLint;
+
+        self.release_immutable_storage();
+
+        unsafe {
+            gl::BindTexture(gl::TEXTURE_2D, self.id as GLuint);
+
+            for (level, data) in levels.iter().enumerate() {
+                let level_width = if (width >> level) > 1 {width >> level} else {1};
+                let level_height = if (height >> level) > 1 {height >> level} else {1};
+                let size = format.data_size(level_width, level_height);
+
+                if data.len() < size {
+                    panic!(
+                        "Mip level {:?} of {:?} needs {:?} bytes, got {:?}",
+                        level, format, size, data.len()
+                    );
+                }
+
+                gl::CompressedTexImage2D(
+                    gl::TEXTURE_2D, level as GLint, gl_format, level_width as GLsizei, level_height as GLsizei,
+                    0, size as GLsizei, data.as_ptr() as *const GLvoid
+                );
+            }
+
+            if !context.is_es() || context.major() >= 3 {
+                gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MAX_LEVEL, (levels.len() - 1) as GLint);
+            }
+
+            gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MAG_FILTER, gl_mag_filter as GLint);
+            gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MIN_FILTER, gl_min_filter as GLint);
+
+            gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_WRAP_S, gl_wrap);
+            gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_WRAP_T, gl_wrap);
+
+            gl::BindTexture(gl::TEXTURE_2D, 0);
+        }
+        gl_check!("Texture::set_compressed2d");
+        self.kind = gl::TEXTURE_2D;
+        self.set_metadata(
+            width, height, levels.len(), InternalFormat::RGBA, TextureFormat::RGBA, TextureKind::UnsignedByte
+        );
+        self.max_level = levels.len() - 1;
+        self.compressed_format = Some(format);
+        self.wrap = wrap;
+        self.filter = filter;
+
+        self
+    }
+
+    #[inline]
+    pub fn has_texture_storage(context: &Context) -> bool {
+        let major = context.major();
+        let minor = context.minor();
+
+        gl::TexStorage2D::is_loaded() && if context.is_es() {
+            major >= 3 || context.has_extenstion("GL_EXT_texture_storage")
+        } else {
+            major > 4 || (major == 4 && minor >= 2) || context.has_extenstion("GL_ARB_texture_storage")
+        }
+    }
+
+    #[inline]
+    pub fn set_storage2d(
+        &mut self,
+        context: &Context,
+        size: &[usize; 2],
+        levels: usize,
+        internal_format: InternalFormat,
+        wrap: TextureWrap,
+        filter: FilterMode
+    ) -> &mut Self {
+        let (width, height) = (size[0], size[1]);
+
+        if !internal_format.is_sized() {
+            panic!("Texture::set_storage2d requires a sized internal format, got {:?}", internal_format);
+        }
+
+        let max_levels = mip_levels(width, height);
+        let levels = if levels < 1 {1} else if levels > max_levels {max_levels} else {levels};
+        let format = internal_format.format();
+        let immutable = Self::has_texture_storage(context);
+
+        let (internal_format, kind) = if !immutable && context.is_es() && context.major() < 3 {
+            match es2_base_format(|name| context.has_extenstion(name), internal_format) {
+                Some(base_format) => base_format,
+                None => panic!(
+                    "Texture::set_storage2d can not allocate {:?} on {:?} without texture storage",
+                    internal_format, context.version()
+                ),
+            }
+        } else {
+            (internal_format, internal_format.kinds()[0])
+        };
+
+        let gl_mag_filter;
+        let gl_min_filter;
+
+        if filter == FilterMode::None {
+            gl_mag_filter = gl::NEAREST;
+            gl_min_filter = if levels > 1 {gl::LINEAR_MIPMAP_NEAREST} else {gl::NEAREST};
+        } else {
+            gl_mag_filter = gl::LINEAR;
+            gl_min_filter = if levels > 1 {gl::LINEAR_MIPMAP_LINEAR} else {gl::LINEAR};
+        }
+
+        let gl_internal_format = internal_format.to_gl();
+        let gl_wrap = wrap.to_gl() as GLint;
+
+        self.release_immutable_storage();
+
+        unsafe {
+            gl::BindTexture(gl::TEXTURE_2D, self.id as GLuint);
+
+            if immutable {
+                gl::TexStorage2D(
+                    gl::TEXTURE_2D, levels as GLsizei, gl_internal_format, width as GLsizei, height as GLsizei
+                );
+            } else {
+                for level in 0..levels {
+                    let level_width = if (width >> level) > 1 {width >> level} else {1};
+                    let level_height = if (height >> level) > 1 {height >> level} else {1};
+
+                    gl::TexImage2D(
+                        gl::TEXTURE_2D, level as GLint, gl_internal_format as GLint,
+                        level_width as GLsizei, level_height as GLsizei,
+                        0, format.to_gl(), kind.to_gl(), ptr::null()
+                    );
+                }
+                if !context.is_es() || context.major() >= 3 {
+                    gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MAX_LEVEL, (levels - 1) as GLint);
+                }
+            }
+
+            gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MAG_FILTER, gl_mag_filter as GLint);
+            gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MIN_FILTER, gl_min_filter as GLint);
+
+            gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_WRAP_S, gl_wrap);
+            gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_WRAP_T, gl_wrap);
+
+            gl::BindTexture(gl::TEXTURE_2D, 0);
+        }
+        gl_check!("Texture::set_storage2d");
+        self.kind = gl::TEXTURE_2D;
+        self.set_metadata(width, height, levels, internal_format, format, kind);
+        self.wrap = wrap;
+        self.filter = filter;
+        self.max_level = levels - 1;
+        self.immutable = immutable;
+
+        self
+    }
+
+    #[inline]
+    pub fn set_multisample2d(
+        &mut self,
+        context: &Context,
+        width: usize,
+        height: usize,
+        samples: usize,
+        internal_format: InternalFormat,
+        fixed_sample_locations: bool
+    ) -> &mut Self {
+        if !internal_format.is_sized() {
+            panic!("Texture::set_multisample2d requires a sized internal format, got {:?}", internal_format);
+        }
+
+        let immutable = context.is_es();
+        let loaded = if immutable {
+            gl::TexStorage2DMultisample::is_loaded()
+        } else {
+            gl::TexImage2DMultisample::is_loaded()
+        };
+        if !context.has_multisample_textures() || !loaded {
+            panic!(
+                "Texture::set_multisample2d requires OpenGL 3.2, OpenGL ES 3.1 or GL_ARB_texture_multisample, got {:?}",
+                context.version()
+            );
+        }
+
+        let max_samples = max_texture_samples(
+            internal_format, context.max_samples(), context.max_integer_samples(), context.max_depth_texture_samples()
+        );
+        let samples = if samples > max_samples {max_samples} else {samples};
+        let samples = if samples < 1 {1} else {samples};
+        let gl_fixed_sample_locations = if fixed_sample_locations {gl::TRUE} else {gl::FALSE};
+
+        self.release_immutable_storage();
+
+        unsafe {
+            gl::BindTexture(gl::TEXTURE_2D_MULTISAMPLE, self.id as GLuint);
+
+            if immutable {
+                gl::TexStorage2DMultisample(
+                    gl::TEXTURE_2D_MULTISAMPLE, samples as GLsizei, internal_format.to_gl(),
+                    width as GLsizei, height as GLsizei, gl_fixed_sample_locations
+                );
+            } else {
+                gl::TexImage2DMultisample(
+                    gl::TEXTURE_2D_MULTISAMPLE, samples as GLsizei, internal_format.to_gl(),
+                    width as GLsizei, height as GLsizei, gl_fixed_sample_locations
+                );
+            }
+
+            gl::BindTexture(gl::TEXTURE_2D_MULTISAMPLE, 0);
+        }
+        gl_check!("Texture::set_multisample2d");
+        self.kind = gl::TEXTURE_2D_MULTISAMPLE;
+
+        let format = internal_format.format();
+        let kind = internal_format.kinds()[0];
+        self.set_metadata(width, height, 1, internal_format, format, kind);
+        self.samples = samples;
+        self.immutable = immutable;
+
+        self
+    }
+
     #[inline]
     pub fn set_data2d<T>(
         &mut self,
         context: &Context,
         width: usize,
         height: usize,
-        format: TextureFormat,
-        kind: TextureKind,
-        wrap: TextureWrap,
-        filter: FilterMode,
-        generate_mipmap: bool,
+        options: &ImageOptions,
+        data: &[T],
+    ) -> &mut Self {
+        let internal_format = default_internal_format(context, options.format, options.kind);
+        self.set_sized_data2d(context, width, height, internal_format, options, data)
+    }
+
+    #[inline]
+    pub fn set_sized_data2d<T>(
+        &mut self,
+        context: &Context,
+        width: usize,
+        height: usize,
+        internal_format: InternalFormat,
+        options: &ImageOptions,
         data: &[T],
     ) -> &mut Self {
+        let ImageOptions { format, kind, wrap, filter, generate_mipmap } = *options;
+        check_data_len("Texture::set_sized_data2d", &TextureRegion::new(0, 0, width, height), format, kind, data);
+
         texture_options!(
-            context, width, height, format, kind, wrap, filter, generate_mipmap,
+            context, width, height, internal_format, format, kind, wrap, filter, generate_mipmap,
             gl_major, gl_minor,
-            gl_pot, gl_format, gl_kind, gl_wrap, gl_mag_filter, gl_min_filter
+            gl_can_mipmap, gl_internal_format, gl_format, gl_kind, gl_wrap, gl_mag_filter, gl_min_filter
         );
 
+        self.release_immutable_storage();
+
         unsafe {
             gl::BindTexture(gl::TEXTURE_2D, self.id as GLuint);
 
             gl::TexImage2D(
-                gl::TEXTURE_2D, 0, gl_format as GLint, width as GLsizei, height as GLsizei,
-                0, gl_format, gl_kind, mem::transmute(data.as_ptr())
+                gl::TEXTURE_2D, 0, gl_internal_format, width as GLsizei, height as GLsizei,
+                0, gl_format, gl_kind, data.as_ptr() as *const GLvoid
             );
 
             gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MAG_FILTER, gl_mag_filter as GLint);
@@ -138,37 +720,54 @@ impl Texture {
             gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_WRAP_S, gl_wrap);
             gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_WRAP_T, gl_wrap);
 
-            generate_mipmap!(self.id, generate_mipmap, gl_major, gl_minor, gl_pot);
+            generate_mipmap!(self.id, gl::TEXTURE_2D, generate_mipmap, gl_major, gl_minor, gl_can_mipmap);
 
             gl::BindTexture(gl::TEXTURE_2D, 0);
         }
+        gl_check!("Texture::set_sized_data2d");
         self.kind = gl::TEXTURE_2D;
+        self.set_metadata(
+            width, height, if generate_mipmap && gl_can_mipmap {mip_levels(width, height)} else {1},
+            internal_format, format, kind
+        );
+        self.wrap = wrap;
+        self.filter = filter;
 
         self
     }
 
     #[inline]
-    pub fn set_null2d(
+    pub fn set_null2d(&mut self, context: &Context, width: usize, height: usize, options: &ImageOptions) -> &mut Self {
+        let internal_format = default_internal_format(context, options.format, options.kind);
+        self.set_sized_null2d(context, width, height, internal_format, options)
+    }
+
+    #[inline]
+    pub fn set_sized_null2d(
         &mut self,
         context: &Context,
         width: usize,
         height: usize,
-        format: TextureFormat,
-        kind: TextureKind,
-        wrap: TextureWrap,
-        filter: FilterMode,
-        generate_mipmap: bool
+        internal_format: InternalFormat,
+        options: &ImageOptions
     ) -> &mut Self {
+        let ImageOptions { format, kind, wrap, filter, generate_mipmap } = *options;
+
         texture_options!(
-            context, width, height, format, kind, wrap, filter, generate_mipmap,
+            context, width, height, internal_format, format, kind, wrap, filter, generate_mipmap,
             gl_major, gl_minor,
-            gl_pot, gl_format, gl_kind, gl_wrap, gl_mag_filter, gl_min_filter
+            gl_can_mipmap, gl_internal_format, gl_format, gl_kind, gl_wrap, gl_mag_filter, gl_min_filter
         );
 
+        self.release_immutable_storage();
+
         unsafe {
             gl::BindTexture(gl::TEXTURE_2D, self.id as GLuint);
 
-            gl::TexImage2D(gl::TEXTURE_2D, 0, gl_format as GLint, width as GLsizei, height as GLsizei, 0, gl_format, gl_kind, ptr::null());
+            gl::TexImage2D(
+                gl::TEXTURE_2D, 0, gl_internal_format, width as GLsizei, height as GLsizei,
+                0, gl_format, gl_kind, ptr::null()
+            );
 
             gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MAG_FILTER, gl_mag_filter as GLint);
             gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MIN_FILTER, gl_min_filter as GLint);
@@ -176,12 +775,940 @@ impl Texture {
             gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_WRAP_S, gl_wrap);
             gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_WRAP_T, gl_wrap);
 
-            generate_mipmap!(self.id, generate_mipmap, gl_major, gl_minor, gl_pot);
+            generate_mipmap!(self.id, gl::TEXTURE_2D, generate_mipmap, gl_major, gl_minor, gl_can_mipmap);
 
             gl::BindTexture(gl::TEXTURE_2D, 0);
         }
+        gl_check!("Texture::set_sized_null2d");
         self.kind = gl::TEXTURE_2D;
+        self.set_metadata(
+            width, height, if generate_mipmap && gl_can_mipmap {mip_levels(width, height)} else {1},
+            internal_format, format, kind
+        );
+        self.wrap = wrap;
+        self.filter = filter;
 
         self
     }
+
+    #[inline]
+    pub fn set_cube_faces<T>(
+        &mut self,
+        context: &Context,
+        size: usize,
+        options: &ImageOptions,
+        faces: &[&[T]; 6],
+    ) -> &mut Self {
+        let internal_format = default_internal_format(context, options.format, options.kind);
+        self.set_sized_cube_faces(context, size, internal_format, options, faces)
+    }
+
+    #[inline]
+    pub fn set_sized_cube_faces<T>(
+        &mut self,
+        context: &Context,
+        size: usize,
+        internal_format: InternalFormat,
+        options: &ImageOptions,
+        faces: &[&[T]; 6],
+    ) -> &mut Self {
+        let ImageOptions { format, kind, wrap, filter, generate_mipmap } = *options;
+        for data in faces.iter() {
+            check_data_len("Texture::set_sized_cube_faces", &TextureRegion::new(0, 0, size, size), format, kind, data);
+        }
+
+        if size > context.max_cube_texture_size() {
+            panic!(
+                "Cube map size {:?} exceeds max cube texture size {:?}",
+                size, context.max_cube_texture_size()
+            );
+        }
+
+        texture_options!(
+            context, size, size, internal_format, format, kind, wrap, filter, generate_mipmap,
+            gl_major, gl_minor,
+            gl_can_mipmap, gl_internal_format, gl_format, gl_kind, gl_wrap, gl_mag_filter, gl_min_filter
+        );
+
+        self.release_immutable_storage();
+
+        unsafe {
+            gl::BindTexture(gl::TEXTURE_CUBE_MAP, self.id as GLuint);
+
+            for (face, data) in CUBE_FACES.iter().zip(faces.iter()) {
+                gl::TexImage2D(
+                    face.to_gl(), 0, gl_internal_format, size as GLsizei, size as GLsizei,
+                    0, gl_format, gl_kind, data.as_ptr() as *const GLvoid
+                );
+            }
+
+            gl::TexParameteri(gl::TEXTURE_CUBE_MAP, gl::TEXTURE_MAG_FILTER, gl_mag_filter as GLint);
+            gl::TexParameteri(gl::TEXTURE_CUBE_MAP, gl::TEXTURE_MIN_FILTER, gl_min_filter as GLint);
+
+            gl::TexParameteri(gl::TEXTURE_CUBE_MAP, gl::TEXTURE_WRAP_S, gl_wrap);
+            gl::TexParameteri(gl::TEXTURE_CUBE_MAP, gl::TEXTURE_WRAP_T, gl_wrap);
+            gl::TexParameteri(gl::TEXTURE_CUBE_MAP, gl::TEXTURE_WRAP_R, gl_wrap);
+
+            generate_mipmap!(self.id, gl::TEXTURE_CUBE_MAP, generate_mipmap, gl_major, gl_minor, gl_can_mipmap);
+
+            gl::BindTexture(gl::TEXTURE_CUBE_MAP, 0);
+        }
+        gl_check!("Texture::set_sized_cube_faces");
+        self.kind = gl::TEXTURE_CUBE_MAP;
+        self.set_metadata(
+            size, size, if generate_mipmap && gl_can_mipmap {mip_levels(size, size)} else {1},
+            internal_format, format, kind
+        );
+        self.wrap = wrap;
+        self.filter = filter;
+
+        self
+    }
+
+    #[inline]
+    pub fn update_cube_face<T>(
+        &mut self,
+        face: CubeFace,
+        region: &TextureRegion,
+        format: TextureFormat,
+        kind: TextureKind,
+        data: &[T],
+    ) -> &mut Self {
+        if self.kind != gl::TEXTURE_CUBE_MAP {
+            panic!("Texture::update_cube_face called on a texture that is not a cube map");
+        }
+        check_data_len("Texture::update_cube_face", region, format, kind, data);
+
+        unsafe {
+            gl::BindTexture(gl::TEXTURE_CUBE_MAP, self.id as GLuint);
+
+            gl::TexSubImage2D(
+                face.to_gl(), 0, region.x as GLint, region.y as GLint,
+                region.width as GLsizei, region.height as GLsizei,
+                format.to_gl(), kind.to_gl(), data.as_ptr() as *const GLvoid
+            );
+
+            gl::BindTexture(gl::TEXTURE_CUBE_MAP, 0);
+        }
+        gl_check!("Texture::update_cube_face");
+
+        self
+    }
+
+    #[inline]
+    pub fn set_data3d<T>(&mut self, context: &Context, size: &[usize; 3], options: &ImageOptions, data: &[T]) -> &mut Self {
+        let internal_format = default_internal_format(context, options.format, options.kind);
+        self.set_sized_data3d(context, size, internal_format, options, data)
+    }
+
+    #[inline]
+    pub fn set_sized_data3d<T>(
+        &mut self,
+        context: &Context,
+        size: &[usize; 3],
+        internal_format: InternalFormat,
+        options: &ImageOptions,
+        data: &[T],
+    ) -> &mut Self {
+        let region = TextureRegion::new3d(0, 0, 0, size[0], size[1], size[2]);
+        check_data_len("Texture::set_sized_data3d", &region, options.format, options.kind, data);
+        self.set_image3d(context, gl::TEXTURE_3D, &region, internal_format, options, data.as_ptr() as *const GLvoid)
+    }
+
+    #[inline]
+    pub fn set_null3d(&mut self, context: &Context, size: &[usize; 3], options: &ImageOptions) -> &mut Self {
+        let region = TextureRegion::new3d(0, 0, 0, size[0], size[1], size[2]);
+        let internal_format = default_internal_format(context, options.format, options.kind);
+        self.set_image3d(context, gl::TEXTURE_3D, &region, internal_format, options, ptr::null())
+    }
+
+    #[inline]
+    pub fn set_data2d_array<T>(
+        &mut self,
+        context: &Context,
+        size: &[usize; 3],
+        options: &ImageOptions,
+        data: &[T],
+    ) -> &mut Self {
+        let internal_format = default_internal_format(context, options.format, options.kind);
+        self.set_sized_data2d_array(context, size, internal_format, options, data)
+    }
+
+    #[inline]
+    pub fn set_sized_data2d_array<T>(
+        &mut self,
+        context: &Context,
+        size: &[usize; 3],
+        internal_format: InternalFormat,
+        options: &ImageOptions,
+        data: &[T],
+    ) -> &mut Self {
+        let region = TextureRegion::new3d(0, 0, 0, size[0], size[1], size[2]);
+        check_data_len("Texture::set_sized_data2d_array", &region, options.format, options.kind, data);
+        self.set_image3d(
+            context, gl::TEXTURE_2D_ARRAY, &region, internal_format, options, data.as_ptr() as *const GLvoid
+        )
+    }
+
+    #[inline]
+    pub fn set_null2d_array(&mut self, context: &Context, size: &[usize; 3], options: &ImageOptions) -> &mut Self {
+        let region = TextureRegion::new3d(0, 0, 0, size[0], size[1], size[2]);
+        let internal_format = default_internal_format(context, options.format, options.kind);
+        self.set_image3d(context, gl::TEXTURE_2D_ARRAY, &region, internal_format, options, ptr::null())
+    }
+
+    #[inline]
+    fn set_image3d(
+        &mut self,
+        context: &Context,
+        target: GLenum,
+        region: &TextureRegion,
+        internal_format: InternalFormat,
+        options: &ImageOptions,
+        data: *const GLvoid
+    ) -> &mut Self {
+        let ImageOptions { format, kind, wrap, filter, generate_mipmap } = *options;
+        let (width, height, depth) = (region.width, region.height, region.depth);
+
+        if target == gl::TEXTURE_3D {
+            let max_size = context.max_3d_texture_size();
+            if width > max_size || height > max_size || depth > max_size {
+                panic!("3D texture size {:?}x{:?}x{:?} exceeds max 3D texture size {:?}", width, height, depth, max_size);
+            }
+        } else if depth > context.max_array_texture_layers() {
+            panic!(
+                "Texture array layers {:?} exceeds max array texture layers {:?}",
+                depth, context.max_array_texture_layers()
+            );
+        }
+
+        texture_options!(
+            context, width, height, internal_format, format, kind, wrap, filter, generate_mipmap,
+            gl_major, gl_minor,
+            gl_can_mipmap, gl_internal_format, gl_format, gl_kind, gl_wrap, gl_mag_filter, gl_min_filter
+        );
+
+        self.release_immutable_storage();
+
+        unsafe {
+            gl::BindTexture(target, self.id as GLuint);
+
+            gl::TexImage3D(
+                target, 0, gl_internal_format, width as GLsizei, height as GLsizei, depth as GLsizei,
+                0, gl_format, gl_kind, data
+            );
+
+            gl::TexParameteri(target, gl::TEXTURE_MAG_FILTER, gl_mag_filter as GLint);
+            gl::TexParameteri(target, gl::TEXTURE_MIN_FILTER, gl_min_filter as GLint);
+
+            gl::TexParameteri(target, gl::TEXTURE_WRAP_S, gl_wrap);
+            gl::TexParameteri(target, gl::TEXTURE_WRAP_T, gl_wrap);
+            if target == gl::TEXTURE_3D {
+                gl::TexParameteri(target, gl::TEXTURE_WRAP_R, gl_wrap);
+            }
+
+            generate_mipmap!(self.id, target, generate_mipmap, gl_major, gl_minor, gl_can_mipmap);
+
+            gl::BindTexture(target, 0);
+        }
+        gl_check!("Texture::set_image3d");
+        self.kind = target;
+
+        let levels = if !(generate_mipmap && gl_can_mipmap) {
+            1
+        } else if target == gl::TEXTURE_3D {
+            mip_levels(if width > height {width} else {height}, depth)
+        } else {
+            mip_levels(width, height)
+        };
+        self.set_metadata(width, height, levels, internal_format, format, kind);
+        self.wrap = wrap;
+        self.filter = filter;
+        self.depth = depth;
+
+        self
+    }
+
+    #[inline]
+    pub fn update_sub_image3d<T>(
+        &mut self,
+        region: &TextureRegion,
+        format: TextureFormat,
+        kind: TextureKind,
+        data: &[T],
+    ) -> &mut Self {
+        if self.kind != gl::TEXTURE_3D && self.kind != gl::TEXTURE_2D_ARRAY {
+            panic!("Texture::update_sub_image3d called on a texture that is not 3D or a 2D array");
+        }
+        check_region("Texture::update_sub_image3d", region, self.width, self.height, self.depth);
+        check_data_len("Texture::update_sub_image3d", region, format, kind, data);
+
+        unsafe {
+            gl::BindTexture(self.kind, self.id as GLuint);
+
+            gl::TexSubImage3D(
+                self.kind, 0, region.x as GLint, region.y as GLint, region.z as GLint,
+                region.width as GLsizei, region.height as GLsizei, region.depth as GLsizei,
+                format.to_gl(), kind.to_gl(), data.as_ptr() as *const GLvoid
+            );
+
+            gl::BindTexture(self.kind, 0);
+        }
+        gl_check!("Texture::update_sub_image3d");
+
+        self
+    }
+
+    #[inline]
+    pub fn update_layer<T>(
+        &mut self,
+        layer: usize,
+        region: &TextureRegion,
+        format: TextureFormat,
+        kind: TextureKind,
+        data: &[T],
+    ) -> &mut Self {
+        let region = TextureRegion::new3d(region.x, region.y, layer, region.width, region.height, 1);
+        self.update_sub_image3d(&region, format, kind, data)
+    }
+
+    #[inline]
+    pub fn set_level_data2d<T>(
+        &mut self,
+        level: usize,
+        format: TextureFormat,
+        kind: TextureKind,
+        data: &[T],
+    ) -> &mut Self {
+        if self.kind != gl::TEXTURE_2D {
+            panic!("Texture::set_level_data2d called on a texture that is not 2D");
+        }
+        self.set_level_image(gl::TEXTURE_2D, level, format, kind, data);
+        gl_check!("Texture::set_level_data2d");
+        self
+    }
+
+    #[inline]
+    pub fn set_cube_face_level<T>(
+        &mut self,
+        face: CubeFace,
+        level: usize,
+        format: TextureFormat,
+        kind: TextureKind,
+        data: &[T],
+    ) -> &mut Self {
+        if self.kind != gl::TEXTURE_CUBE_MAP {
+            panic!("Texture::set_cube_face_level called on a texture that is not a cube map");
+        }
+        self.set_level_image(face.to_gl(), level, format, kind, data);
+        gl_check!("Texture::set_cube_face_level");
+        self
+    }
+
+    #[inline]
+    fn set_level_image<T>(&mut self, target: GLenum, level: usize, format: TextureFormat, kind: TextureKind, data: &[T]) {
+        let width = if (self.width >> level) > 1 {self.width >> level} else {1};
+        let height = if (self.height >> level) > 1 {self.height >> level} else {1};
+        check_data_len("Texture::set_level_image", &TextureRegion::new(0, 0, width, height), format, kind, data);
+
+        unsafe {
+            gl::BindTexture(self.kind, self.id as GLuint);
+
+            if self.immutable {
+                gl::TexSubImage2D(
+                    target, level as GLint, 0, 0, width as GLsizei, height as GLsizei,
+                    format.to_gl(), kind.to_gl(), data.as_ptr() as *const GLvoid
+                );
+            } else {
+                gl::TexImage2D(
+                    target, level as GLint, self.internal_format.to_gl() as GLint, width as GLsizei, height as GLsizei,
+                    0, format.to_gl(), kind.to_gl(), data.as_ptr() as *const GLvoid
+                );
+            }
+
+            gl::BindTexture(self.kind, 0);
+        }
+        gl_check!("Texture::set_level_image");
+
+        if level >= self.levels {
+            self.levels = level + 1;
+        }
+    }
+
+    #[inline]
+    pub fn set_level_range(&mut self, context: &Context, base_level: usize, max_level: usize) -> &mut Self {
+        if context.is_es() && context.major() < 3 {
+            panic!("Texture::set_level_range requires OpenGL ES 3.0, got {:?}", context.version());
+        }
+
+        unsafe {
+            gl::BindTexture(self.kind, self.id as GLuint);
+            gl::TexParameteri(self.kind, gl::TEXTURE_BASE_LEVEL, base_level as GLint);
+            gl::TexParameteri(self.kind, gl::TEXTURE_MAX_LEVEL, max_level as GLint);
+            gl::BindTexture(self.kind, 0);
+        }
+        gl_check!("Texture::set_level_range");
+
+        self.base_level = base_level;
+        self.max_level = max_level;
+
+        self
+    }
+
+    #[inline]
+    pub fn generate_mipmap(&mut self, context: &Context) -> &mut Self {
+        let gl_major = context.major();
+        let gl_minor = context.minor();
+        let gl_can_mipmap = gl_major >= 3 || (self.width.is_power_of_two() && self.height.is_power_of_two());
+        let generate_mipmap = true;
+
+        if !gl_can_mipmap {
+            panic!(
+                "Texture::generate_mipmap requires power of two sizes on {:?}, got {:?}x{:?}",
+                context.version(), self.width, self.height
+            );
+        }
+
+        unsafe {
+            gl::BindTexture(self.kind, self.id as GLuint);
+            generate_mipmap!(self.id, self.kind, generate_mipmap, gl_major, gl_minor, gl_can_mipmap);
+            gl::BindTexture(self.kind, 0);
+        }
+        gl_check!("Texture::generate_mipmap");
+
+        self.levels = if self.kind == gl::TEXTURE_3D {
+            mip_levels(if self.width > self.height {self.width} else {self.height}, self.depth)
+        } else {
+            mip_levels(self.width, self.height)
+        };
+
+        self
+    }
+
+    #[inline]
+    pub fn read_image(&self, context: &mut Context, level: usize, format: TextureFormat, kind: TextureKind) -> Vec<u8> {
+        if self.compressed_format.is_some() {
+            panic!("Texture::read_image can not read compressed textures");
+        }
+
+        let width = if (self.width >> level) > 1 {self.width >> level} else {1};
+        let height = if (self.height >> level) > 1 {self.height >> level} else {1};
+        let depth = if self.kind == gl::TEXTURE_3D {
+            if (self.depth >> level) > 1 {self.depth >> level} else {1}
+        } else {
+            self.depth
+        };
+        let size = width * height * depth * kind.pixel_size(format);
+
+        let mut data = Vec::with_capacity(size);
+        data.resize(size, 0u8);
+
+        let mut pack_alignment = 0;
+        unsafe {
+            gl::GetIntegerv(gl::PACK_ALIGNMENT, &mut pack_alignment);
+            gl::PixelStorei(gl::PACK_ALIGNMENT, 1);
+        }
+
+        if !context.is_es() {
+            if self.kind == gl::TEXTURE_CUBE_MAP {
+                panic!("Texture::read_image can not read cube maps, read a face through a Framebuffer instead");
+            }
+
+            unsafe {
+                gl::BindTexture(self.kind, self.id);
+                gl::GetTexImage(
+                    self.kind, level as GLint, format.to_gl(), kind.to_gl(), data.as_mut_ptr() as *mut GLvoid
+                );
+                gl::BindTexture(self.kind, 0);
+            }
+        } else {
+            if self.kind != gl::TEXTURE_2D {
+                panic!("Texture::read_image can only read 2D textures without glGetTexImage");
+            }
+
+            let framebuffer = Framebuffer::new();
+            context.push_state();
+
+            let target = if context.major() >= 3 {
+                context.set_read_framebuffer(Some(&framebuffer), false);
+                gl::READ_FRAMEBUFFER
+            } else {
+                context.set_framebuffer(&framebuffer, false);
+                gl::FRAMEBUFFER
+            };
+
+            let status = unsafe {
+                gl::FramebufferTexture2D(target, gl::COLOR_ATTACHMENT0, gl::TEXTURE_2D, self.id, level as GLint);
+                FramebufferStatus::from_gl(gl::CheckFramebufferStatus(target))
+            };
+            gl_check!("Texture::read_image: attach");
+            if status != FramebufferStatus::Complete {
+                context.pop_state();
+                panic!("Texture::read_image could not read level {:?}: {}", level, status.description());
+            }
+
+            unsafe {
+                gl::ReadPixels(
+                    0, 0, width as GLsizei, height as GLsizei,
+                    format.to_gl(), kind.to_gl(), data.as_mut_ptr() as *mut GLvoid
+                );
+            }
+            gl_check!("Texture::read_image: read pixels");
+            context.pop_state();
+        }
+
+        unsafe { gl::PixelStorei(gl::PACK_ALIGNMENT, pack_alignment); }
+        gl_check!("Texture::read_image");
+
+        data
+    }
+
+    #[inline]
+    fn release_immutable_storage(&mut self) {
+        if self.immutable {
+            let id = self.id;
+            unsafe {
+                gl::GenTextures(1, &mut self.id);
+                gl::DeleteTextures(1, &id);
+            }
+            gl_check!("Texture::release_immutable_storage");
+            self.immutable = false;
+        }
+    }
+
+    #[inline]
+    fn set_metadata(
+        &mut self,
+        width: usize,
+        height: usize,
+        levels: usize,
+        internal_format: InternalFormat,
+        format: TextureFormat,
+        kind: TextureKind
+    ) {
+        self.width = width;
+        self.height = height;
+        self.depth = 1;
+        self.levels = levels;
+        self.samples = 0;
+        self.base_level = 0;
+        self.max_level = 1000;
+        self.immutable = false;
+        self.internal_format = internal_format;
+        self.format = format;
+        self.data_kind = kind;
+        self.compressed_format = None;
+    }
+
+    #[inline]
+    pub fn update_sub_image2d<T>(
+        &mut self,
+        region: &TextureRegion,
+        format: TextureFormat,
+        kind: TextureKind,
+        data: &[T],
+    ) -> &mut Self {
+        if self.kind != gl::TEXTURE_2D {
+            panic!("Texture::update_sub_image2d called on a texture that is not 2D");
+        }
+        check_region("Texture::update_sub_image2d", region, self.width, self.height, 1);
+        check_data_len("Texture::update_sub_image2d", region, format, kind, data);
+
+        unsafe {
+            gl::BindTexture(gl::TEXTURE_2D, self.id as GLuint);
+
+            gl::TexSubImage2D(
+                gl::TEXTURE_2D, 0, region.x as GLint, region.y as GLint,
+                region.width as GLsizei, region.height as GLsizei,
+                format.to_gl(), kind.to_gl(), data.as_ptr() as *const GLvoid
+            );
+
+            gl::BindTexture(gl::TEXTURE_2D, 0);
+        }
+        gl_check!("Texture::update_sub_image2d");
+
+        self
+    }
+
+    #[inline]
+    pub fn update_sub_image2d_from_buffer(
+        &mut self,
+        context: &Context,
+        region: &TextureRegion,
+        format: TextureFormat,
+        kind: TextureKind,
+        buffer: &Buffer,
+        offset: usize,
+    ) -> &mut Self {
+        if self.kind != gl::TEXTURE_2D {
+            panic!("Texture::update_sub_image2d_from_buffer called on a texture that is not 2D");
+        }
+        if buffer.kind() != BufferTarget::PixelUnpack {
+            panic!("Texture::update_sub_image2d_from_buffer requires a PixelUnpack buffer, got {:?}", buffer.kind());
+        }
+
+        check_region("Texture::update_sub_image2d_from_buffer", region, self.width, self.height, 1);
+
+        let size = region.size(format, kind);
+        match offset.checked_add(size) {
+            Some(end) if end <= buffer.size() => (),
+            _ => panic!(
+                "Texture::update_sub_image2d_from_buffer reads {:?} bytes at offset {:?} from a buffer of size {:?}",
+                size, offset, buffer.size()
+            ),
+        }
+
+        // Bound directly so the context does not disable vertex attributes,
+        // restored afterwards in case the cache holds a pixel unpack buffer.
+        let previous_buffer = if context.current_buffer_kind() == gl::PIXEL_UNPACK_BUFFER {
+            context.current_buffer()
+        } else {
+            0
+        };
+
+        unsafe {
+            gl::BindBuffer(gl::PIXEL_UNPACK_BUFFER, buffer.id());
+            gl::BindTexture(gl::TEXTURE_2D, self.id as GLuint);
+
+            gl::TexSubImage2D(
+                gl::TEXTURE_2D, 0, region.x as GLint, region.y as GLint,
+                region.width as GLsizei, region.height as GLsizei,
+                format.to_gl(), kind.to_gl(), offset as *const GLvoid
+            );
+
+            gl::BindTexture(gl::TEXTURE_2D, 0);
+            gl::BindBuffer(gl::PIXEL_UNPACK_BUFFER, previous_buffer);
+        }
+        gl_check!("Texture::update_sub_image2d_from_buffer");
+
+        self
+    }
+}
+
+#[inline]
+fn valid_format<F>(
+    is_es: bool,
+    major: usize,
+    has_extension: F,
+    internal_format: InternalFormat,
+    format: TextureFormat,
+    kind: TextureKind
+) -> bool
+    where F: Fn(&str) -> bool
+{
+    if !is_es {
+        if internal_format.requires_gl3() && major < 3 {
+            return false;
+        }
+        if internal_format.is_integer() != format.is_integer() {
+            return false;
+        }
+        if format.is_integer() && kind.is_float() {
+            return false;
+        }
+
+        let depth_format = format == TextureFormat::DepthComponent || format == TextureFormat::DepthStencil;
+        if internal_format.is_depth() != depth_format {
+            return false;
+        }
+
+        return match kind.packed_formats() {
+            Some(formats) => formats.contains(&format),
+            None => true,
+        };
+    }
+
+    if internal_format.is_sized() {
+        return major >= 3 && internal_format.format() == format && internal_format.kinds().contains(&kind);
+    }
+
+    match internal_format {
+        InternalFormat::Red | InternalFormat::RG => if major >= 3 || !has_extension("GL_EXT_texture_rg") {
+            return false;
+        },
+        InternalFormat::DepthStencil => if major < 3 {
+            return false;
+        },
+        _ => (),
+    }
+
+    let bgra = internal_format == InternalFormat::RGBA && format == TextureFormat::BGRA && (
+        has_extension("GL_EXT_texture_format_BGRA8888") || has_extension("GL_APPLE_texture_format_BGRA8888")
+    );
+    if internal_format.format() != format && !bgra {
+        return false;
+    }
+
+    match kind {
+        TextureKind::Float => !internal_format.is_depth() && has_extension("GL_OES_texture_float"),
+        TextureKind::HalfFloat => !internal_format.is_depth() && has_extension("GL_OES_texture_half_float"),
+        _ => internal_format.kinds().contains(&kind),
+    }
+}
+
+#[inline]
+fn default_internal_format(context: &Context, format: TextureFormat, kind: TextureKind) -> InternalFormat {
+    let internal_format = InternalFormat::from_format(format, kind);
+
+    if !context.is_es() || context.major() < 3 {
+        return internal_format;
+    }
+
+    match (internal_format, kind) {
+        (InternalFormat::Red, TextureKind::UnsignedByte) => InternalFormat::R8,
+        (InternalFormat::Red, TextureKind::HalfFloat) => InternalFormat::R16F,
+        (InternalFormat::Red, TextureKind::Float) => InternalFormat::R32F,
+        (InternalFormat::RG, TextureKind::UnsignedByte) => InternalFormat::RG8,
+        (InternalFormat::RG, TextureKind::HalfFloat) => InternalFormat::RG16F,
+        (InternalFormat::RG, TextureKind::Float) => InternalFormat::RG32F,
+        _ => internal_format,
+    }
+}
+
+#[inline]
+fn gl_internal_format(context: &Context, internal_format: InternalFormat, format: TextureFormat) -> GLint {
+    if
+        context.is_es() &&
+        internal_format == InternalFormat::RGBA &&
+        format == TextureFormat::BGRA &&
+        context.has_extenstion("GL_EXT_texture_format_BGRA8888")
+    {
+        gl::BGRA as GLint
+    } else {
+        internal_format.to_gl() as GLint
+    }
+}
+
+#[inline]
+fn es2_base_format<F>(has_extension: F, internal_format: InternalFormat) -> Option<(InternalFormat, TextureKind)>
+    where F: Fn(&str) -> bool
+{
+    match internal_format {
+        InternalFormat::RGBA8 => Some((InternalFormat::RGBA, TextureKind::UnsignedByte)),
+        InternalFormat::RGB8 => Some((InternalFormat::RGB, TextureKind::UnsignedByte)),
+        InternalFormat::RGB565 => Some((InternalFormat::RGB, TextureKind::UnsignedShort565)),
+        InternalFormat::RGBA4 => Some((InternalFormat::RGBA, TextureKind::UnsignedShort4444)),
+        InternalFormat::RGB5A1 => Some((InternalFormat::RGBA, TextureKind::UnsignedShort5551)),
+        InternalFormat::DepthComponent16 => if has_extension("GL_OES_depth_texture") {
+            Some((InternalFormat::DepthComponent, TextureKind::UnsignedShort))
+        } else {
+            None
+        },
+        _ => None,
+    }
+}
+
+#[inline]
+fn check_data_len<T>(method: &str, region: &TextureRegion, format: TextureFormat, kind: TextureKind, data: &[T]) {
+    let size = region.size(format, kind);
+    let data_size = data.len() * mem::size_of::<T>();
+
+    if data_size < size {
+        panic!(
+            "{} expected at least {:?} bytes for a {:?}x{:?}x{:?} region, got {:?}",
+            method, size, region.width, region.height, region.depth, data_size
+        );
+    }
+}
+
+#[inline]
+fn check_region(method: &str, region: &TextureRegion, width: usize, height: usize, depth: usize) {
+    let inside = |start: usize, size: usize, max: usize| match start.checked_add(size) {
+        Some(end) => end <= max,
+        None => false,
+    };
+
+    if
+        !inside(region.x, region.width, width) ||
+        !inside(region.y, region.height, height) ||
+        !inside(region.z, region.depth, depth)
+    {
+        panic!(
+            "{} region {:?}x{:?}x{:?} at ({:?}, {:?}, {:?}) is outside the {:?}x{:?}x{:?} texture",
+            method, region.width, region.height, region.depth, region.x, region.y, region.z, width, height, depth
+        );
+    }
+}
+
+#[inline]
+fn max_texture_samples(
+    internal_format: InternalFormat, max_samples: usize, max_integer_samples: usize, max_depth_samples: usize
+) -> usize {
+    let max_format_samples = if internal_format.is_depth() || internal_format.is_stencil() {
+        max_depth_samples
+    } else if internal_format.is_integer() {
+        max_integer_samples
+    } else {
+        max_samples
+    };
+
+    if max_format_samples < max_samples {max_format_samples} else {max_samples}
+}
+
+#[inline]
+fn check_wrap(context: &Context, wrap: TextureWrap) {
+    if wrap == TextureWrap::ClampToBorder && !context.has_border_clamp() {
+        panic!("{:?} requires OpenGL ES 3.2 or GL_EXT_texture_border_clamp, got {:?}", wrap, context.version());
+    }
+}
+
+#[inline]
+fn validate_format(context: &Context, internal_format: InternalFormat, format: TextureFormat, kind: TextureKind) {
+    if !Texture::is_valid_format(context, internal_format, format, kind) {
+        panic!(
+            "Invalid texture upload {:?} from {:?} {:?} for {:?}",
+            internal_format, format, kind, context.version()
+        );
+    }
+}
+
+#[inline]
+pub fn null_upload_format<F>(
+    is_es: bool,
+    major: usize,
+    has_extension: F,
+    internal_format: InternalFormat
+) -> Option<(InternalFormat, TextureFormat, TextureKind)>
+    where F: Fn(&str) -> bool
+{
+    if is_es && major < 3 && internal_format.is_sized() {
+        return es2_base_format(&has_extension, internal_format).map(|(base_format, kind)| {
+            (base_format, base_format.format(), kind)
+        });
+    }
+
+    let internal_format = match internal_format {
+        InternalFormat::Red if is_es && major >= 3 => InternalFormat::R8,
+        InternalFormat::RG if is_es && major >= 3 => InternalFormat::RG8,
+        internal_format => internal_format,
+    };
+    let format = internal_format.format();
+
+    internal_format.kinds().iter()
+        .find(|&&kind| valid_format(is_es, major, &has_extension, internal_format, format, kind))
+        .map(|&kind| (internal_format, format, kind))
+}
+
+#[inline]
+pub fn mip_levels(width: usize, height: usize) -> usize {
+    let mut size = if width > height {width} else {height};
+    let mut levels = 1;
+    while size > 1 {
+        size >>= 1;
+        levels += 1;
+    }
+    levels
+}
+
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+
+    fn no_extensions(_: &str) -> bool { false }
+
+    #[test]
+    fn test_valid_format_desktop() {
+        assert!(valid_format(false, 3, no_extensions, InternalFormat::RGBA8, TextureFormat::RGBA, TextureKind::UnsignedByte));
+        assert!(valid_format(false, 3, no_extensions, InternalFormat::RGBA, TextureFormat::BGRA, TextureKind::UnsignedByte));
+        assert!(valid_format(false, 3, no_extensions, InternalFormat::R32F, TextureFormat::Red, TextureKind::Float));
+        assert!(!valid_format(false, 2, no_extensions, InternalFormat::R32F, TextureFormat::Red, TextureKind::Float));
+        assert!(!valid_format(false, 3, no_extensions, InternalFormat::RGBA8UI, TextureFormat::RGBA, TextureKind::UnsignedByte));
+        assert!(!valid_format(false, 3, no_extensions, InternalFormat::RGBA32I, TextureFormat::RGBAInteger, TextureKind::Float));
+        assert!(!valid_format(false, 3, no_extensions, InternalFormat::RGBA8, TextureFormat::DepthComponent, TextureKind::UnsignedInt));
+        assert!(!valid_format(false, 3, no_extensions, InternalFormat::RGB, TextureFormat::RGB, TextureKind::UnsignedShort4444));
+    }
+
+    #[test]
+    fn test_valid_format_es3_tables() {
+        assert!(valid_format(true, 3, no_extensions, InternalFormat::RGBA8, TextureFormat::RGBA, TextureKind::UnsignedByte));
+        assert!(valid_format(true, 3, no_extensions, InternalFormat::RGBA4, TextureFormat::RGBA, TextureKind::UnsignedShort4444));
+        assert!(valid_format(true, 3, no_extensions, InternalFormat::R8, TextureFormat::Red, TextureKind::UnsignedByte));
+        assert!(valid_format(true, 3, no_extensions, InternalFormat::RG16F, TextureFormat::RG, TextureKind::HalfFloat));
+        assert!(!valid_format(true, 3, no_extensions, InternalFormat::RGBA8, TextureFormat::RGBA, TextureKind::Float));
+        assert!(!valid_format(true, 3, no_extensions, InternalFormat::Red, TextureFormat::Red, TextureKind::UnsignedByte));
+        assert!(!valid_format(true, 3, no_extensions, InternalFormat::RG, TextureFormat::RG, TextureKind::UnsignedByte));
+        assert!(!valid_format(true, 3, |name| name == "GL_EXT_texture_rg", InternalFormat::Red, TextureFormat::Red, TextureKind::UnsignedByte));
+    }
+
+    #[test]
+    fn test_valid_format_es2_extensions() {
+        assert!(valid_format(true, 2, no_extensions, InternalFormat::RGBA, TextureFormat::RGBA, TextureKind::UnsignedByte));
+        assert!(!valid_format(true, 2, no_extensions, InternalFormat::RGBA8, TextureFormat::RGBA, TextureKind::UnsignedByte));
+
+        assert!(!valid_format(true, 2, no_extensions, InternalFormat::RGBA, TextureFormat::RGBA, TextureKind::Float));
+        assert!(valid_format(true, 2, |name| name == "GL_OES_texture_float", InternalFormat::RGBA, TextureFormat::RGBA, TextureKind::Float));
+        assert!(!valid_format(true, 2, |name| name == "GL_OES_texture_float", InternalFormat::RGBA, TextureFormat::RGBA, TextureKind::HalfFloat));
+        assert!(valid_format(true, 2, |name| name == "GL_OES_texture_half_float", InternalFormat::RGB, TextureFormat::RGB, TextureKind::HalfFloat));
+
+        assert!(!valid_format(true, 2, no_extensions, InternalFormat::RGBA, TextureFormat::BGRA, TextureKind::UnsignedByte));
+        assert!(valid_format(true, 2, |name| name == "GL_EXT_texture_format_BGRA8888", InternalFormat::RGBA, TextureFormat::BGRA, TextureKind::UnsignedByte));
+
+        assert!(!valid_format(true, 2, no_extensions, InternalFormat::Red, TextureFormat::Red, TextureKind::UnsignedByte));
+        assert!(valid_format(true, 2, |name| name == "GL_EXT_texture_rg", InternalFormat::Red, TextureFormat::Red, TextureKind::UnsignedByte));
+        assert!(!valid_format(true, 2, no_extensions, InternalFormat::DepthStencil, TextureFormat::DepthStencil, TextureKind::UnsignedInt248));
+    }
+
+    #[test]
+    fn test_null_upload_format() {
+        assert_eq!(
+            null_upload_format(false, 3, no_extensions, InternalFormat::RGB10A2),
+            Some((InternalFormat::RGB10A2, TextureFormat::RGBA, TextureKind::UnsignedInt2101010Rev))
+        );
+        assert_eq!(
+            null_upload_format(true, 3, no_extensions, InternalFormat::Red),
+            Some((InternalFormat::R8, TextureFormat::Red, TextureKind::UnsignedByte))
+        );
+        assert_eq!(
+            null_upload_format(true, 2, no_extensions, InternalFormat::RGB565),
+            Some((InternalFormat::RGB, TextureFormat::RGB, TextureKind::UnsignedShort565))
+        );
+        assert_eq!(null_upload_format(true, 2, no_extensions, InternalFormat::Red), None);
+        assert_eq!(null_upload_format(true, 2, no_extensions, InternalFormat::R32F), None);
+    }
+
+    #[test]
+    fn test_image_options() {
+        let mut options = ImageOptions::new(TextureFormat::RGBA, TextureKind::UnsignedByte);
+        assert_eq!(options.wrap(), TextureWrap::Clamp);
+        assert_eq!(options.filter(), FilterMode::Linear);
+        assert!(!options.generate_mipmap());
+
+        options.set_wrap(TextureWrap::Repeat).set_filter(FilterMode::None).set_generate_mipmap(true);
+        assert_eq!(options.format(), TextureFormat::RGBA);
+        assert_eq!(options.kind(), TextureKind::UnsignedByte);
+        assert_eq!(options.wrap(), TextureWrap::Repeat);
+        assert_eq!(options.filter(), FilterMode::None);
+        assert!(options.generate_mipmap());
+    }
+
+    #[test]
+    fn test_max_texture_samples() {
+        assert_eq!(max_texture_samples(InternalFormat::RGBA8, 8, 4, 2), 8);
+        assert_eq!(max_texture_samples(InternalFormat::RGBA8UI, 8, 4, 2), 4);
+        assert_eq!(max_texture_samples(InternalFormat::DepthComponent24, 8, 4, 2), 2);
+        assert_eq!(max_texture_samples(InternalFormat::Depth24Stencil8, 8, 4, 2), 2);
+        assert_eq!(max_texture_samples(InternalFormat::StencilIndex8, 8, 4, 2), 2);
+        assert_eq!(max_texture_samples(InternalFormat::R32I, 4, 16, 16), 4);
+    }
+
+    #[test]
+    fn test_check_region() {
+        check_region("test", &TextureRegion::new(0, 0, 64, 32), 64, 32, 1);
+        check_region("test", &TextureRegion::new(60, 30, 4, 2), 64, 32, 1);
+        check_region("test", &TextureRegion::new3d(0, 0, 3, 8, 8, 1), 8, 8, 4);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_check_region_outside() {
+        check_region("test", &TextureRegion::new(60, 0, 8, 8), 64, 32, 1);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_check_region_overflow() {
+        check_region("test", &TextureRegion::new(usize::max_value(), 0, 2, 8), 64, 32, 1);
+    }
 }
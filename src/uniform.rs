@@ -134,6 +134,7 @@ macro_rules! create_simple_uniform {
                     Some(value) => {
                         copy_array(&mut self.value, value);
                         unsafe { gl::$func(self.location, 1, value.as_ptr()); }
+                        gl_check!(concat!(stringify!($t), "::set"));
                         true
                     },
                     None => panic!(
@@ -150,6 +151,7 @@ macro_rules! create_simple_uniform {
                         if force || ne_array(&self.value, value) {
                             copy_array(&mut self.value, value);
                             unsafe { gl::$func(self.location, 1, value.as_ptr()); }
+                            gl_check!(concat!(stringify!($t), "::set"));
                             true
                         } else {
                             false
@@ -182,6 +184,7 @@ macro_rules! create_simple_single_uniform {
                     Some(value) => {
                         self.value = *value;
                         unsafe { gl::$func(self.location, *value) };
+                        gl_check!(concat!(stringify!($t), "::set"));
                         true
                     },
                     None => panic!(
@@ -198,6 +201,7 @@ macro_rules! create_simple_single_uniform {
                         if force || self.value != *value {
                             self.value = *value;
                             unsafe { gl::$func(self.location, value.clone()); }
+                            gl_check!(concat!(stringify!($t), "::set"));
                             true
                         } else {
                             false
@@ -230,6 +234,7 @@ macro_rules! create_matrix_uniform {
                     Some(value) => {
                         copy_array(&mut self.value, value);
                         unsafe { gl::$func(self.location, 1, gl::FALSE, value.as_ptr()); }
+                        gl_check!(concat!(stringify!($t), "::set"));
                         true
                     },
                     None => panic!(
@@ -246,6 +251,7 @@ macro_rules! create_matrix_uniform {
                         if force || ne_array(&self.value, value) {
                             copy_array(&mut self.value, value);
                             unsafe { gl::$func(self.location, 1, gl::FALSE, value.as_ptr()); }
+                            gl_check!(concat!(stringify!($t), "::set"));
                             true
                         } else {
                             false
@@ -313,6 +319,7 @@ macro_rules! create_size_simple_uniform {
                             self.size as GLint,
                             value.as_ptr()
                         );
+                        gl_check!(concat!(stringify!($t), "::set"));
                         true
                     },
                     None => panic!(
@@ -349,6 +356,7 @@ macro_rules! create_size_matrix_uniform {
                         gl::FALSE,
                         (value as *const Any) as *const $kind
                     );
+                    gl_check!(concat!(stringify!($t), "::set"));
                     true
                 }
             }
@@ -460,6 +468,10 @@ pub fn new_uniform(name: String, kind: GLenum, size: usize, location: GLint) ->
     } else {
         match kind {
             gl::SAMPLER_2D => Box::new(UniformTexture::new(name, kind, size, location)) as Box<Uniform>,
+            gl::SAMPLER_CUBE => Box::new(UniformTexture::new(name, kind, size, location)) as Box<Uniform>,
+            gl::SAMPLER_3D => Box::new(UniformTexture::new(name, kind, size, location)) as Box<Uniform>,
+            gl::SAMPLER_2D_ARRAY => Box::new(UniformTexture::new(name, kind, size, location)) as Box<Uniform>,
+            gl::SAMPLER_2D_MULTISAMPLE => Box::new(UniformTexture::new(name, kind, size, location)) as Box<Uniform>,
 
             gl::FLOAT => Box::new(Uniform1f::new(name, kind, size, location)) as Box<Uniform>,
             gl::INT => Box::new(Uniform1i::new(name, kind, size, location)) as Box<Uniform>,
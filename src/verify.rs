@@ -0,0 +1,333 @@
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use core::fmt::{self, Debug, Write};
+
+use gl;
+use gl::types::*;
+
+use context::Context;
+use enums::{Blending, CullFace, Depth};
+
+
+static FLOAT_TOLERANCE: f32 = 0.0001;
+
+
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct StateMismatch {
+    name: &'static str,
+    cached: String,
+    actual: String,
+}
+
+impl StateMismatch {
+    #[inline]
+    pub fn new<C: Debug, A: Debug>(name: &'static str, cached: C, actual: A) -> Self {
+        let mut cached_string = String::new();
+        let mut actual_string = String::new();
+        let _ = write!(cached_string, "{:?}", cached);
+        let _ = write!(actual_string, "{:?}", actual);
+
+        StateMismatch {
+            name: name,
+            cached: cached_string,
+            actual: actual_string,
+        }
+    }
+
+    #[inline(always)]
+    pub fn name(&self) -> &'static str { self.name }
+    #[inline(always)]
+    pub fn cached(&self) -> &String { &self.cached }
+    #[inline(always)]
+    pub fn actual(&self) -> &String { &self.actual }
+}
+
+impl fmt::Display for StateMismatch {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}: cached {} but GL has {}", self.name, self.cached, self.actual)
+    }
+}
+
+
+macro_rules! check_state {
+    ($mismatches: ident, $name: expr, $cached: expr, $actual: expr) => (
+        {
+            let cached = $cached;
+            let actual = $actual;
+
+            if cached != actual {
+                $mismatches.push(StateMismatch::new($name, cached, actual));
+            }
+        }
+    );
+}
+
+macro_rules! check_state_approx {
+    ($mismatches: ident, $name: expr, $cached: expr, $actual: expr) => (
+        {
+            let cached = $cached;
+            let actual = $actual;
+
+            if !floats_equal(&cached, &actual) {
+                $mismatches.push(StateMismatch::new($name, cached, actual));
+            }
+        }
+    );
+}
+
+
+#[inline]
+fn floats_equal(a: &[f32], b: &[f32]) -> bool {
+    a.len() == b.len() && a.iter().zip(b.iter()).all(|(a, b)| {
+        let difference = a - b;
+        difference < FLOAT_TOLERANCE && difference > -FLOAT_TOLERANCE
+    })
+}
+
+
+#[inline]
+unsafe fn get_integer(pname: GLenum) -> GLint {
+    let mut value = 0;
+    gl::GetIntegerv(pname, &mut value);
+    value
+}
+#[inline]
+unsafe fn get_integers4(pname: GLenum) -> [GLint; 4] {
+    let mut value = [0; 4];
+    gl::GetIntegerv(pname, value.as_mut_ptr());
+    value
+}
+#[inline]
+unsafe fn get_float(pname: GLenum) -> GLfloat {
+    let mut value = 0f32;
+    gl::GetFloatv(pname, &mut value);
+    value
+}
+#[inline]
+unsafe fn get_floats4(pname: GLenum) -> [GLfloat; 4] {
+    let mut value = [0f32; 4];
+    gl::GetFloatv(pname, value.as_mut_ptr());
+    value
+}
+#[inline]
+unsafe fn get_floats2(pname: GLenum) -> [GLfloat; 2] {
+    let mut value = [0f32; 2];
+    gl::GetFloatv(pname, value.as_mut_ptr());
+    value
+}
+#[inline]
+unsafe fn get_booleans4(pname: GLenum) -> [bool; 4] {
+    let mut value = [gl::FALSE; 4];
+    gl::GetBooleanv(pname, value.as_mut_ptr());
+    [value[0] == gl::TRUE, value[1] == gl::TRUE, value[2] == gl::TRUE, value[3] == gl::TRUE]
+}
+#[inline]
+unsafe fn is_enabled(capability: GLenum) -> bool {
+    gl::IsEnabled(capability) == gl::TRUE
+}
+
+#[inline]
+fn buffer_binding(kind: GLenum) -> Option<GLenum> {
+    match kind {
+        gl::ARRAY_BUFFER => Some(gl::ARRAY_BUFFER_BINDING),
+        gl::ELEMENT_ARRAY_BUFFER => Some(gl::ELEMENT_ARRAY_BUFFER_BINDING),
+        gl::COPY_READ_BUFFER => Some(gl::COPY_READ_BUFFER_BINDING),
+        gl::COPY_WRITE_BUFFER => Some(gl::COPY_WRITE_BUFFER_BINDING),
+        gl::PIXEL_PACK_BUFFER => Some(gl::PIXEL_PACK_BUFFER_BINDING),
+        gl::PIXEL_UNPACK_BUFFER => Some(gl::PIXEL_UNPACK_BUFFER_BINDING),
+        gl::UNIFORM_BUFFER => Some(gl::UNIFORM_BUFFER_BINDING),
+        _ => None,
+    }
+}
+
+#[inline]
+fn texture_binding(kind: GLenum) -> Option<GLenum> {
+    match kind {
+        gl::TEXTURE_2D => Some(gl::TEXTURE_BINDING_2D),
+        gl::TEXTURE_CUBE_MAP => Some(gl::TEXTURE_BINDING_CUBE_MAP),
+        gl::TEXTURE_3D => Some(gl::TEXTURE_BINDING_3D),
+        gl::TEXTURE_2D_ARRAY => Some(gl::TEXTURE_BINDING_2D_ARRAY),
+        gl::TEXTURE_2D_MULTISAMPLE => Some(gl::TEXTURE_BINDING_2D_MULTISAMPLE),
+        _ => None,
+    }
+}
+
+#[inline]
+fn blend_factors(blending: Blending) -> Option<[GLenum; 4]> {
+    match blending {
+        Blending::Additive => Some([gl::SRC_ALPHA, gl::ONE, gl::SRC_ALPHA, gl::ONE]),
+        Blending::Subtractive => Some([gl::ZERO, gl::ONE_MINUS_SRC_COLOR, gl::ZERO, gl::ONE_MINUS_SRC_COLOR]),
+        Blending::Multiply => Some([gl::ZERO, gl::SRC_COLOR, gl::ZERO, gl::SRC_COLOR]),
+        Blending::Default => Some([gl::SRC_ALPHA, gl::ONE_MINUS_SRC_ALPHA, gl::ONE, gl::ONE_MINUS_SRC_ALPHA]),
+        Blending::None => None,
+    }
+}
+
+#[inline]
+fn cull_face_mode(cull_face: CullFace) -> Option<GLenum> {
+    match cull_face {
+        CullFace::Back => Some(gl::BACK),
+        CullFace::Front => Some(gl::FRONT),
+        CullFace::FrontAndBack => Some(gl::FRONT_AND_BACK),
+        CullFace::None => None,
+    }
+}
+
+
+pub fn verify_state(context: &Context) -> Vec<StateMismatch> {
+    let mut mismatches = Vec::new();
+
+    unsafe {
+        check_state!(mismatches, "current_program", context.current_program(), get_integer(gl::CURRENT_PROGRAM) as GLuint);
+        check_state!(mismatches, "current_vertex_array", context.current_vertex_array(), get_integer(gl::VERTEX_ARRAY_BINDING) as GLuint);
+        check_state!(mismatches, "current_framebuffer", context.current_framebuffer(), get_integer(gl::DRAW_FRAMEBUFFER_BINDING) as GLuint);
+        check_state!(mismatches, "current_read_framebuffer", context.current_read_framebuffer(), get_integer(gl::READ_FRAMEBUFFER_BINDING) as GLuint);
+        check_state!(mismatches, "current_renderbuffer", context.current_renderbuffer(), get_integer(gl::RENDERBUFFER_BINDING) as GLuint);
+
+        if let Some(binding) = buffer_binding(context.current_buffer_kind()) {
+            check_state!(mismatches, "current_buffer", context.current_buffer(), get_integer(binding) as GLuint);
+        }
+
+        if context.current_texture_index() >= 0 {
+            check_state!(
+                mismatches, "current_texture_index",
+                gl::TEXTURE0 + context.current_texture_index() as GLenum, get_integer(gl::ACTIVE_TEXTURE) as GLenum
+            );
+        }
+        if let Some(binding) = texture_binding(context.current_texture_kind()) {
+            check_state!(mismatches, "current_texture", context.current_texture(), get_integer(binding) as GLuint);
+        }
+
+        check_state!(mismatches, "blending_disabled", context.blending_disabled(), !is_enabled(gl::BLEND));
+        check_state!(mismatches, "cull_face_disabled", context.cull_face_disabled(), !is_enabled(gl::CULL_FACE));
+        check_state!(mismatches, "depth_test_disabled", context.depth_test_disabled(), !is_enabled(gl::DEPTH_TEST));
+        check_state!(mismatches, "stencil_test_disabled", context.stencil_test_disabled(), !is_enabled(gl::STENCIL_TEST));
+        check_state!(mismatches, "scissor_test_disabled", context.scissor_test_disabled(), !is_enabled(gl::SCISSOR_TEST));
+
+        if let Some(factors) = blend_factors(context.blending()) {
+            check_state!(
+                mismatches, "blend_equation",
+                [gl::FUNC_ADD, gl::FUNC_ADD],
+                [get_integer(gl::BLEND_EQUATION_RGB) as GLenum, get_integer(gl::BLEND_EQUATION_ALPHA) as GLenum]
+            );
+            check_state!(
+                mismatches, "blending",
+                factors,
+                [
+                    get_integer(gl::BLEND_SRC_RGB) as GLenum,
+                    get_integer(gl::BLEND_DST_RGB) as GLenum,
+                    get_integer(gl::BLEND_SRC_ALPHA) as GLenum,
+                    get_integer(gl::BLEND_DST_ALPHA) as GLenum,
+                ]
+            );
+        }
+        if let Some(mode) = cull_face_mode(context.cull_face()) {
+            check_state!(mismatches, "cull_face", mode, get_integer(gl::CULL_FACE_MODE) as GLenum);
+        }
+        if context.depth_func() != Depth::None {
+            check_state!(mismatches, "depth_func", context.depth_func().to_gl(), get_integer(gl::DEPTH_FUNC) as GLenum);
+        }
+
+        let stencil = context.stencil();
+        if stencil.func() != Depth::None {
+            check_state!(mismatches, "stencil.func", stencil.func().to_gl(), get_integer(gl::STENCIL_FUNC) as GLenum);
+            check_state!(mismatches, "stencil.reference", stencil.reference(), get_integer(gl::STENCIL_REF));
+            check_state!(mismatches, "stencil.read_mask", stencil.read_mask(), get_integer(gl::STENCIL_VALUE_MASK) as GLuint);
+            check_state!(mismatches, "stencil.fail", stencil.fail().to_gl(), get_integer(gl::STENCIL_FAIL) as GLenum);
+            check_state!(mismatches, "stencil.depth_fail", stencil.depth_fail().to_gl(), get_integer(gl::STENCIL_PASS_DEPTH_FAIL) as GLenum);
+            check_state!(mismatches, "stencil.pass", stencil.pass().to_gl(), get_integer(gl::STENCIL_PASS_DEPTH_PASS) as GLenum);
+        }
+        check_state!(mismatches, "stencil.write_mask", stencil.write_mask(), get_integer(gl::STENCIL_WRITEMASK) as GLuint);
+
+        if let Some(scissor) = context.scissor() {
+            let actual = get_integers4(gl::SCISSOR_BOX);
+            check_state!(
+                mismatches, "scissor",
+                scissor,
+                [actual[0] as usize, actual[1] as usize, actual[2] as usize, actual[3] as usize]
+            );
+        }
+        check_state!(mismatches, "color_mask", *context.color_mask(), get_booleans4(gl::COLOR_WRITEMASK));
+
+        if context.viewport_valid() {
+            let viewport = get_integers4(gl::VIEWPORT);
+            check_state!(
+                mismatches, "viewport",
+                [context.viewport_x(), context.viewport_y(), context.viewport_width(), context.viewport_height()],
+                [viewport[0] as usize, viewport[1] as usize, viewport[2] as usize, viewport[3] as usize]
+            );
+        }
+
+        if context.clear_color_valid() {
+            check_state_approx!(mismatches, "clear_color", *context.clear_color(), get_floats4(gl::COLOR_CLEAR_VALUE));
+        }
+        check_state_approx!(mismatches, "clear_depth", [context.clear_depth() as f32], [get_float(gl::DEPTH_CLEAR_VALUE)]);
+        check_state!(mismatches, "clear_stencil", context.clear_stencil(), get_integer(gl::STENCIL_CLEAR_VALUE) as isize);
+
+        check_state!(mismatches, "depth_write", context.depth_write(), get_booleans4(gl::DEPTH_WRITEMASK)[0]);
+        check_state_approx!(
+            mismatches, "depth_range",
+            [context.depth_range_near() as f32, context.depth_range_far() as f32], get_floats2(gl::DEPTH_RANGE)
+        );
+        check_state_approx!(mismatches, "line_width", [context.line_width()], [get_float(gl::LINE_WIDTH)]);
+
+        let enabled_attributes = context.enabled_attributes();
+        for index in 0..enabled_attributes.len() {
+            let mut enabled = 0;
+            gl::GetVertexAttribiv(index as GLuint, gl::VERTEX_ATTRIB_ARRAY_ENABLED, &mut enabled);
+            check_state!(mismatches, "enabled_attributes", (index, enabled_attributes[index]), (index, enabled != 0));
+        }
+
+        let current_samplers = context.current_samplers();
+        if context.has_samplers() && !current_samplers.is_empty() {
+            let active_texture = get_integer(gl::ACTIVE_TEXTURE) as GLenum;
+
+            for unit in 0..current_samplers.len() {
+                gl::ActiveTexture(gl::TEXTURE0 + unit as GLenum);
+                check_state!(
+                    mismatches, "current_samplers",
+                    (unit, current_samplers[unit]), (unit, get_integer(gl::SAMPLER_BINDING) as GLuint)
+                );
+            }
+
+            gl::ActiveTexture(active_texture);
+        }
+    }
+
+    mismatches
+}
+
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+
+    #[test]
+    fn test_state_mismatch_debug_values() {
+        let mismatch = StateMismatch::new("viewport", [0usize, 0, 1, 1], [0usize, 0, 640, 480]);
+
+        assert_eq!(mismatch.name(), "viewport");
+        assert_eq!(mismatch.cached(), "[0, 0, 1, 1]");
+        assert_eq!(mismatch.actual(), "[0, 0, 640, 480]");
+    }
+
+    #[test]
+    fn test_state_mismatch_display() {
+        let mismatch = StateMismatch::new("depth_write", true, false);
+        let mut string = String::new();
+        let _ = write!(string, "{}", mismatch);
+
+        assert_eq!(string, "depth_write: cached true but GL has false");
+    }
+
+    #[test]
+    fn test_floats_equal() {
+        assert!(floats_equal(&[0f32, 1f32], &[0f32, 1f32]));
+        assert!(floats_equal(&[0.5f32], &[0.50001f32]));
+        assert!(!floats_equal(&[0.5f32], &[0.6f32]));
+        assert!(!floats_equal(&[0f32, 1f32], &[0f32]));
+    }
+}
@@ -3,6 +3,9 @@ use core::ops::Drop;
 use gl;
 use gl::types::*;
 
+use context::Context;
+use debug;
+
 
 pub struct VertexArray {
     id: GLuint,
@@ -13,6 +16,7 @@ impl Drop for VertexArray {
     fn drop(&mut self) {
         if self.id != 0 {
             unsafe { gl::DeleteVertexArrays(1, &self.id); }
+            gl_check!("VertexArray::drop");
         }
     }
 }
@@ -24,10 +28,17 @@ impl VertexArray {
             id: {
                 let mut id = 0;
                 unsafe { gl::GenVertexArrays(1, &mut id); }
+                gl_check!("VertexArray::new");
                 id
             },
         }
     }
     #[inline(always)]
     pub fn id(&self) -> GLuint { self.id }
+
+    #[inline]
+    pub fn set_label(&self, context: &Context, label: &str) -> &Self {
+        debug::object_label(context, gl::VERTEX_ARRAY, self.id, label);
+        self
+    }
 }